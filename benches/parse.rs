@@ -0,0 +1,76 @@
+//! Parse throughput over a corpus of rules in the style of Apple's quirks file.
+//!
+//! The parsing itself happens in the `password-rules-parser` crate, so changes to how it
+//! allocates have to be made there; these benchmarks are here to measure them (and this crate's
+//! own work on top of the parsed rules). To compare a parser release against the current one,
+//! save a baseline before upgrading and compare against it afterwards:
+//!
+//! ```text
+//! cargo bench --bench parse -- --save-baseline before
+//! cargo bench --bench parse -- --baseline before
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use password_rules_checker::{parse_password_rules_recover, Lint, ToRulesString};
+use password_rules_parser::parse_password_rules;
+
+const CORPUS: &[&str] = &[
+    "minlength: 8; maxlength: 16; required: lower; required: upper; required: digit;",
+    "minlength: 8; maxlength: 32; required: lower, upper; required: digit; allowed: [-().&@?'#,/\"+];",
+    "minlength: 6; maxlength: 20; allowed: lower, upper, digit, [-_.@];",
+    "minlength: 12; required: lower; required: upper; required: digit; required: [!#$%&*?@];",
+    "minlength: 8; maxlength: 64; max-consecutive: 3; required: lower; required: upper; required: digit; required: special;",
+    "minlength: 10; maxlength: 30; required: upper; required: lower; required: digit; allowed: [~!@#$%^&*()_+];",
+    "minlength: 8; maxlength: 15; required: lower; required: upper; required: digit; required: [@#$%^&*]; max-consecutive: 2;",
+    "maxlength: 20; required: upper; required: digit; allowed: lower;",
+    "minlength: 5; allowed: ascii-printable;",
+    "minlength: 8; maxlength: 12; allowed: unicode;",
+    "minlength: 6; maxlength: 8; allowed: digit;",
+    "minlength: 4; maxlength: 4; required: digit;",
+    "minlength: 8; required: lower; required: upper; required: digit; allowed: [-!\"#$%&'()*+,./:;<=>?@[^_`{|}~]];",
+    "minlength: 9; maxlength: 50; required: lower, upper, digit, special;",
+    "minlength: 7; maxlength: 128; required: [ABCDEFGHJKLMNPQRSTUVWXYZ]; allowed: lower, digit;",
+];
+
+const ERRONEOUS: &[&str] = &[
+    "minlength: x; required: upper; allow: lower; maxlength: 3",
+    "minlength: 8; maxlength 16; required: lowr; allowed: [abc",
+    "required: upper; required: digit; allowed: ; max-consecutive: -1;",
+];
+
+fn parse_benchmarks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    group.throughput(Throughput::Elements(CORPUS.len() as u64));
+
+    group.bench_function("parse corpus", |b| {
+        b.iter(|| {
+            for rules in CORPUS {
+                black_box(parse_password_rules(black_box(rules), true).unwrap());
+            }
+        })
+    });
+
+    group.bench_function("parse and check corpus", |b| {
+        b.iter(|| {
+            for rules in CORPUS {
+                let parsed = parse_password_rules(black_box(rules), true).unwrap();
+                black_box(parsed.necessary_allowed());
+                black_box(parsed.unsatisfiable_reasons());
+                black_box(parsed.to_rules_string());
+            }
+        })
+    });
+
+    group.finish();
+
+    c.bench_function("recover from errors", |b| {
+        b.iter(|| {
+            for rules in ERRONEOUS {
+                black_box(parse_password_rules_recover(black_box(rules), true));
+            }
+        })
+    });
+}
+
+criterion_group!(benches, parse_benchmarks);
+criterion_main!(benches);
@@ -0,0 +1,124 @@
+//! Constructing password rules without writing them in the rules language
+
+use crate::syntax::{self, ToRulesString};
+use password_rules_parser::{parse_password_rules, CharacterClass, PasswordRules};
+use std::fmt::{self, Display, Formatter};
+
+/// The classes the parser would produce for `property: classes;`.
+///
+/// The parser merges the classes in a property into as few classes as it can and drops
+/// characters it doesn't support (anything non-ASCII in a custom class), so this runs the classes
+/// through it rather than reimplementing that.
+fn parsed_classes(property: &str, classes: &[CharacterClass]) -> Option<Vec<CharacterClass>> {
+    let rules = format!("{}: {};", property, syntax::format_classes(classes));
+    let mut parsed = parse_password_rules(&rules, false).ok()?;
+    match property {
+        "allowed" => Some(parsed.allowed),
+        _ => Some(parsed.required.pop().unwrap_or_default()),
+    }
+}
+
+/// A builder for `PasswordRules`, for constructing rules in code rather than by parsing a rules
+/// string.
+///
+/// The rules it builds are the same as the parser would produce for the equivalent rules string,
+/// and its `Display` implementation writes that string (see [`ToRulesString`]), with the clauses
+/// in a canonical order. Parsing the string gives back exactly the built rules, as long as at
+/// least one property is set.
+///
+/// A builder with no properties set builds `PasswordRules::default()` and writes an empty string,
+/// which the parser rejects as empty input rather than parsing back to the same rules. What
+/// applies to a site without rules (see [`crate::Defaults::apple_default`]) is
+/// `allowed: ascii-printable;`, which a builder writes once that class is allowed.
+///
+/// ```
+/// use password_rules_checker::builder::PasswordRulesBuilder;
+/// use password_rules_parser::{parse_password_rules, CharacterClass};
+///
+/// let builder = PasswordRulesBuilder::new()
+///     .min_length(8)
+///     .require(vec![CharacterClass::Upper])
+///     .require(vec![CharacterClass::Digit, CharacterClass::Special])
+///     .allow(vec![CharacterClass::Lower]);
+///
+/// assert_eq!(
+///     builder.to_string(),
+///     "minlength: 8; required: upper; required: digit, special; allowed: lower;"
+/// );
+/// assert_eq!(
+///     builder.build(),
+///     parse_password_rules(
+///         "minlength: 8; required: upper; required: digit, special; allowed: lower;",
+///         false
+///     )
+///     .unwrap()
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PasswordRulesBuilder {
+    rules: PasswordRules,
+}
+
+impl PasswordRulesBuilder {
+    /// A builder for rules with no properties set
+    pub fn new() -> Self {
+        PasswordRulesBuilder::default()
+    }
+
+    /// Set `minlength`
+    pub fn min_length(mut self, min_length: u32) -> Self {
+        self.rules.min_length = Some(min_length);
+        self
+    }
+
+    /// Set `maxlength`
+    pub fn max_length(mut self, max_length: u32) -> Self {
+        self.rules.max_length = Some(max_length);
+        self
+    }
+
+    /// Set `max-consecutive`
+    pub fn max_consecutive(mut self, max_consecutive: u32) -> Self {
+        self.rules.max_consecutive = Some(max_consecutive);
+        self
+    }
+
+    /// Add a `required` group, which a password satisfies with a character from any one of
+    /// `classes`
+    pub fn require(mut self, classes: impl IntoIterator<Item = CharacterClass>) -> Self {
+        self.rules.required.push(classes.into_iter().collect());
+        self
+    }
+
+    /// Add `classes` to `allowed`
+    pub fn allow(mut self, classes: impl IntoIterator<Item = CharacterClass>) -> Self {
+        self.rules.allowed.extend(classes);
+        self
+    }
+
+    /// Build the rules.
+    ///
+    /// The classes in `allowed` and in each `required` group are merged the way the parser
+    /// merges them (so `upper, [A]` becomes `upper`), which drops non-ASCII characters from
+    /// custom classes; `required` groups left with no classes are dropped, as the parser drops
+    /// them.
+    pub fn build(&self) -> PasswordRules {
+        let mut rules = self.rules.clone();
+        if !rules.allowed.is_empty() {
+            rules.allowed = parsed_classes("allowed", &rules.allowed).unwrap_or(rules.allowed);
+        }
+        rules.required = rules
+            .required
+            .into_iter()
+            .map(|classes| parsed_classes("required", &classes).unwrap_or(classes))
+            .filter(|classes| !classes.is_empty())
+            .collect();
+        rules
+    }
+}
+
+impl Display for PasswordRulesBuilder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.build().to_rules_string())
+    }
+}
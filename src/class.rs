@@ -0,0 +1,196 @@
+//! Extension methods for the parser's `CharacterClass`
+//!
+//! `CharacterClass` already implements `Ord`, so lists of classes can be sorted deterministically:
+//! the built-in classes come first, in the order `upper`, `lower`, `digit`, `special`,
+//! `ascii-printable`, `unicode`, followed by custom classes ordered by their characters. Two
+//! custom classes with the same characters in a different order sort differently, so
+//! [`CharacterClassExt::canonicalize`] them first when that matters.
+
+use password_rules_parser::CharacterClass;
+use std::hash::{Hash, Hasher};
+
+/// The characters in the `special` class
+const SPECIAL_CHARS: &str = " !\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~";
+
+/// Extension methods for `CharacterClass`
+pub trait CharacterClassExt {
+    /// Whether `c` is a member of the class.
+    ///
+    /// `unicode` contains every character, and `ascii-printable` contains the 95 characters from
+    /// `' '` (0x20) through `'~'` (0x7E), so it includes the space. This agrees with
+    /// [`CharacterClassExt::members`].
+    fn contains(&self, c: char) -> bool;
+
+    /// Every character in the class, or `None` for `unicode`, which is too large to enumerate.
+    ///
+    /// This differs from the parser's `CharacterClass::chars`, which treats `unicode` as if it
+    /// were `ascii-printable`.
+    fn members(&self) -> Option<Vec<char>>;
+
+    /// The class with the characters of a custom class sorted and de-duplicated, so that custom
+    /// classes containing the same characters compare equal. Built-in classes are returned as is.
+    ///
+    /// Rules produced by the parser are already canonical, since it merges every character from
+    /// a property into a fixed set of classes; this is for classes constructed some other way,
+    /// and is never applied automatically.
+    fn canonicalize(&self) -> CharacterClass;
+
+    /// The canonicalized class with the characters of a custom class converted to Unicode
+    /// Normalization Form C, so that classes containing the same visual characters compare equal
+    /// however they were encoded: a decomposed `e` followed by a combining acute accent becomes
+    /// the single character `é`. Built-in classes are returned as is.
+    ///
+    /// The characters are normalized together, as they'd be written between the brackets, since
+    /// a combining character composes with the character before it. This changes the characters
+    /// in the class, so it's never applied automatically; passwords being checked against the
+    /// normalized class should be normalized the same way.
+    ///
+    /// ```
+    /// use password_rules_checker::CharacterClassExt;
+    /// use password_rules_parser::CharacterClass;
+    ///
+    /// let composed = CharacterClass::Custom(vec!['\u{e9}']);
+    /// let decomposed = CharacterClass::Custom(vec!['e', '\u{301}']);
+    ///
+    /// assert_ne!(composed, decomposed);
+    /// assert_eq!(composed.to_nfc(), decomposed.to_nfc());
+    /// ```
+    #[cfg(feature = "nfc")]
+    fn to_nfc(&self) -> CharacterClass;
+}
+
+impl CharacterClassExt for CharacterClass {
+    fn contains(&self, c: char) -> bool {
+        match self {
+            CharacterClass::Upper => c.is_ascii_uppercase(),
+            CharacterClass::Lower => c.is_ascii_lowercase(),
+            CharacterClass::Digit => c.is_ascii_digit(),
+            CharacterClass::Special => SPECIAL_CHARS.contains(c),
+            CharacterClass::AsciiPrintable => (' '..='~').contains(&c),
+            CharacterClass::Unicode => true,
+            CharacterClass::Custom(chars) => chars.contains(&c),
+        }
+    }
+
+    fn members(&self) -> Option<Vec<char>> {
+        match self {
+            CharacterClass::Unicode => None,
+            class => Some(class.chars()),
+        }
+    }
+
+    fn canonicalize(&self) -> CharacterClass {
+        match self {
+            CharacterClass::Custom(chars) => {
+                let mut chars = chars.clone();
+                chars.sort_unstable();
+                chars.dedup();
+                CharacterClass::Custom(chars)
+            }
+            class => class.clone(),
+        }
+    }
+
+    #[cfg(feature = "nfc")]
+    fn to_nfc(&self) -> CharacterClass {
+        use unicode_normalization::UnicodeNormalization;
+
+        match self {
+            CharacterClass::Custom(chars) => {
+                CharacterClass::Custom(chars.iter().copied().nfc().collect()).canonicalize()
+            }
+            class => class.clone(),
+        }
+    }
+}
+
+/// A `CharacterClass` that can be hashed, such as to use it as a `HashMap` key.
+///
+/// `CharacterClass` belongs to the parser, so it can't implement `Hash` here. The class is
+/// canonicalized (see [`CharacterClassExt::canonicalize`]) when the key is made, so keys are
+/// equal, and hash equally, exactly when the canonicalized classes are equal: custom classes with
+/// the same characters in a different order make the same key.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ClassKey(CharacterClass);
+
+impl ClassKey {
+    pub fn new(class: &CharacterClass) -> Self {
+        ClassKey(class.canonicalize())
+    }
+
+    /// The canonicalized class
+    pub fn class(&self) -> &CharacterClass {
+        &self.0
+    }
+}
+
+impl Hash for ClassKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_class(&self.0, state);
+    }
+}
+
+/// Hash `class` as it is, without canonicalizing it
+pub(crate) fn hash_class<H: Hasher>(class: &CharacterClass, state: &mut H) {
+    match class {
+        CharacterClass::Upper => 0.hash(state),
+        CharacterClass::Lower => 1.hash(state),
+        CharacterClass::Digit => 2.hash(state),
+        CharacterClass::Special => 3.hash(state),
+        CharacterClass::AsciiPrintable => 4.hash(state),
+        CharacterClass::Unicode => 5.hash(state),
+        CharacterClass::Custom(chars) => {
+            6.hash(state);
+            chars.hash(state);
+        }
+    }
+}
+
+/// The canonicalized classes in `classes`, sorted and de-duplicated so that their order doesn't
+/// matter
+pub(crate) fn canonical_classes(classes: &[CharacterClass]) -> Vec<CharacterClass> {
+    let mut classes: Vec<CharacterClass> = classes.iter().map(|c| c.canonicalize()).collect();
+    classes.sort();
+    classes.dedup();
+    classes
+}
+
+/// The fewest classes that contain exactly the characters in `chars`, the way the parser
+/// canonicalizes the classes in a property.
+///
+/// Built-in classes are used wherever all of their characters are present, and any leftover
+/// characters are collected into a single custom class.
+pub(crate) fn classes_from_chars(chars: &[char]) -> Vec<CharacterClass> {
+    let mut chars = chars.to_vec();
+    chars.sort_unstable();
+    chars.dedup();
+
+    let is_complete = |class: &CharacterClass| class.chars().iter().all(|c| chars.contains(c));
+    if is_complete(&CharacterClass::AsciiPrintable)
+        && chars.iter().all(|&c| (' '..='~').contains(&c))
+    {
+        return vec![CharacterClass::AsciiPrintable];
+    }
+
+    let mut classes: Vec<CharacterClass> = [
+        CharacterClass::Upper,
+        CharacterClass::Lower,
+        CharacterClass::Digit,
+        CharacterClass::Special,
+    ]
+    .iter()
+    .filter(|class| is_complete(class))
+    .cloned()
+    .collect();
+
+    let custom: Vec<char> = chars
+        .iter()
+        .copied()
+        .filter(|&c| !classes.iter().any(|class| class.contains(c)))
+        .collect();
+    if !custom.is_empty() {
+        classes.push(CharacterClass::Custom(custom));
+    }
+
+    classes
+}
@@ -0,0 +1,60 @@
+//! The default password rules that apply when a site doesn't specify its own
+
+use password_rules_parser::{CharacterClass, PasswordRules};
+
+/// Extension methods for the defaults of `PasswordRules`
+pub trait Defaults {
+    /// The rules that apply to a site that has no quirk.
+    ///
+    /// Apple's [password rules documentation] only defines one default: when a rule neither
+    /// allows nor requires any classes, `ascii-printable` is allowed. It doesn't impose a minimum
+    /// or maximum length, a `max-consecutive` limit, or any required classes, so neither does
+    /// this; the result is equivalent to parsing `allowed: ascii-printable;`.
+    ///
+    /// ```
+    /// use password_rules_checker::Defaults;
+    /// use password_rules_parser::{parse_password_rules, PasswordRules};
+    ///
+    /// let rules = parse_password_rules("allowed: ascii-printable;", false).unwrap();
+    ///
+    /// assert_eq!(PasswordRules::apple_default(), rules);
+    /// ```
+    ///
+    /// [password rules documentation]: https://developer.apple.com/password-rules/
+    fn apple_default() -> Self;
+
+    /// The rules with Apple's defaults filled in, so that what applies is explicit rather than
+    /// implied by what was left out.
+    ///
+    /// The only documented default is that `ascii-printable` is allowed when no classes are
+    /// allowed or required (which is what the parser's `supply_default` flag does while
+    /// parsing). The optional numeric properties don't have defaults: leaving one out means
+    /// there's no limit, so they're left as `None`.
+    ///
+    /// ```
+    /// use password_rules_checker::Defaults;
+    /// use password_rules_parser::parse_password_rules;
+    ///
+    /// let rules = parse_password_rules("minlength: 8;", false).unwrap();
+    ///
+    /// assert_eq!(rules.with_defaults(), parse_password_rules("minlength: 8;", true).unwrap());
+    /// ```
+    fn with_defaults(&self) -> Self;
+}
+
+impl Defaults for PasswordRules {
+    fn apple_default() -> Self {
+        PasswordRules {
+            allowed: vec![CharacterClass::AsciiPrintable],
+            ..PasswordRules::default()
+        }
+    }
+
+    fn with_defaults(&self) -> Self {
+        let mut rules = self.clone();
+        if rules.allowed.is_empty() && rules.required.is_empty() {
+            rules.allowed.push(CharacterClass::AsciiPrintable);
+        }
+        rules
+    }
+}
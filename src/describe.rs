@@ -0,0 +1,129 @@
+//! Describing password rules in plain English
+
+use crate::syntax;
+use password_rules_parser::{CharacterClass, PasswordRules};
+
+/// The noun for a single character from `class`, such as "uppercase letter"
+fn class_noun(class: &CharacterClass) -> String {
+    match class {
+        CharacterClass::Upper => "uppercase letter".to_string(),
+        CharacterClass::Lower => "lowercase letter".to_string(),
+        CharacterClass::Digit => "digit".to_string(),
+        CharacterClass::Special => "special character".to_string(),
+        CharacterClass::AsciiPrintable => "ASCII-printable character".to_string(),
+        CharacterClass::Unicode => "Unicode character".to_string(),
+        CharacterClass::Custom(_) => format!("character from `{}`", syntax::format_class(class)),
+    }
+}
+
+/// The plural noun for the characters in `class`, such as "uppercase letters"
+fn class_noun_plural(class: &CharacterClass) -> String {
+    match class {
+        CharacterClass::Custom(_) => format!("the characters `{}`", syntax::format_class(class)),
+        class => format!("{}s", class_noun(class)),
+    }
+}
+
+/// `n` followed by `noun`, pluralized if `n` isn't 1
+fn count(n: u32, noun: &str) -> String {
+    if n == 1 {
+        format!("{} {}", n, noun)
+    } else {
+        format!("{} {}s", n, noun)
+    }
+}
+
+/// Join `items` into an English list, such as "a, b, and c"
+fn english_list(items: &[String], conjunction: &str) -> String {
+    match items {
+        [] => String::new(),
+        [item] => item.clone(),
+        [first, second] => format!("{} {} {}", first, conjunction, second),
+        [rest @ .., last] => format!("{}, {} {}", rest.join(", "), conjunction, last),
+    }
+}
+
+/// Extension methods for describing `PasswordRules`
+pub trait Describe {
+    /// Describe the rules in a sentence of plain English, such as for telling someone why their
+    /// password was rejected.
+    ///
+    /// Every property that's set gets a clause in the sentence, and properties that aren't set
+    /// are left out (other than a missing `maxlength` when there's a `minlength`, which is
+    /// called out).
+    ///
+    /// ```
+    /// use password_rules_checker::Describe;
+    /// use password_rules_parser::parse_password_rules;
+    ///
+    /// let rules = parse_password_rules(
+    ///     "minlength: 8; maxlength: 20; required: upper; required: digit; max-consecutive: 3;",
+    ///     true,
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(
+    ///     rules.describe(),
+    ///     "Passwords must be 8–20 characters, contain at least one uppercase letter and one \
+    ///     digit, and forbid more than 3 repeated characters in a row."
+    /// );
+    /// ```
+    fn describe(&self) -> String;
+}
+
+impl Describe for PasswordRules {
+    fn describe(&self) -> String {
+        let mut clauses = Vec::new();
+
+        let length = match (self.min_length, self.max_length) {
+            (Some(min_length), Some(max_length)) if min_length == max_length => {
+                Some(format!("be exactly {}", count(min_length, "character")))
+            }
+            (Some(min_length), Some(max_length)) => {
+                Some(format!("be {}–{} characters", min_length, max_length))
+            }
+            (Some(min_length), None) => Some(format!(
+                "be at least {} (with no maximum length)",
+                count(min_length, "character")
+            )),
+            (None, Some(max_length)) => {
+                Some(format!("be at most {}", count(max_length, "character")))
+            }
+            (None, None) => None,
+        };
+        clauses.extend(length);
+
+        let required: Vec<String> = self
+            .required
+            .iter()
+            .map(|classes| {
+                let nouns: Vec<String> = classes.iter().map(class_noun).collect();
+                format!("one {}", english_list(&nouns, "or"))
+            })
+            .collect();
+        if !required.is_empty() {
+            clauses.push(format!(
+                "contain at least {}",
+                english_list(&required, "and")
+            ));
+        }
+
+        if !self.allowed.is_empty() {
+            let allowed: Vec<String> = self.allowed.iter().map(class_noun_plural).collect();
+            clauses.push(format!("allow {}", english_list(&allowed, "and")));
+        }
+
+        if let Some(max_consecutive) = self.max_consecutive {
+            clauses.push(format!(
+                "forbid more than {} in a row",
+                count(max_consecutive, "repeated character")
+            ));
+        }
+
+        if clauses.is_empty() {
+            "Passwords aren't restricted in any way.".to_string()
+        } else {
+            format!("Passwords must {}.", english_list(&clauses, "and"))
+        }
+    }
+}
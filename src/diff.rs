@@ -0,0 +1,265 @@
+//! Field-by-field comparison of two sets of password rules
+
+use crate::class::{canonical_classes, hash_class};
+use crate::defaults::Defaults;
+use crate::lint::Lint;
+use crate::rules::PasswordRulesExt;
+use crate::syntax;
+use password_rules_parser::{CharacterClass, PasswordRules};
+use serde::Serialize;
+use std::fmt::{self, Display, Formatter};
+use std::hash::{Hash, Hasher};
+
+/// A single property that differs between two sets of password rules
+#[derive(Debug, Serialize)]
+pub struct FieldDiff {
+    /// The name of the `PasswordRules` field that differs
+    pub field: &'static str,
+    /// The value of the field in the left-hand rules, rendered in the rules syntax
+    pub left: String,
+    /// The value of the field in the right-hand rules, rendered in the rules syntax
+    pub right: String,
+}
+
+impl Display for FieldDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: `{}` != `{}`", self.field, self.left, self.right)
+    }
+}
+
+/// Every property that differs between the rules for a site in two files
+#[derive(Debug, Serialize)]
+pub struct SiteDiff {
+    pub site: String,
+    pub fields: Vec<FieldDiff>,
+}
+
+impl Display for SiteDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}:", self.site)?;
+        for field in self.fields.iter() {
+            writeln!(f, "  {}", field)?;
+        }
+        Ok(())
+    }
+}
+
+fn format_number(n: Option<u32>) -> String {
+    n.map_or_else(|| "none".to_string(), |n| n.to_string())
+}
+
+fn format_required(required: &[Vec<CharacterClass>]) -> String {
+    if required.is_empty() {
+        return "none".to_string();
+    }
+
+    required
+        .iter()
+        .map(|classes| syntax::format_classes(classes))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Whether two lists of required classes contain the same groups, ignoring order
+fn required_equivalent(required: &[Vec<CharacterClass>], other: &[Vec<CharacterClass>]) -> bool {
+    if required.len() != other.len() {
+        return false;
+    }
+
+    // Canonicalize the other required classes so matched classes can be removed from the copy
+    let mut other_required: Vec<Vec<CharacterClass>> = other
+        .iter()
+        .map(|classes| canonical_classes(classes))
+        .collect();
+    for required_class in required.iter().map(|classes| canonical_classes(classes)) {
+        match other_required.iter().position(|c| *c == required_class) {
+            // Remove the class so it can't be matched again
+            Some(i) => {
+                other_required.remove(i);
+            }
+            None => return false,
+        }
+    }
+
+    true
+}
+
+/// Options that control how strictly [`diff_rules_with`] compares two sets of rules
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiffOptions {
+    /// Compare `allowed` as written, rather than first dropping the classes that are redundant
+    /// because they're also required. With this set, `allowed: upper, lower; required: upper;`
+    /// differs from `allowed: lower; required: upper;`, even though both permit the same
+    /// characters.
+    pub strict_allowed: bool,
+    /// Compare `allowed` as a sequence of classes rather than as a set, so the same classes in a
+    /// different order are a difference.
+    ///
+    /// Apple treats `allowed` as a set: the order the classes are written in doesn't change
+    /// which passwords are accepted, which is why this is off by default. The parser puts the
+    /// classes in a canonical order, so for this to catch reordering, `allowed` has to be set
+    /// to the classes in the order they're written (see [`syntax::written_allowed`]) before
+    /// diffing.
+    pub ordered_allowed: bool,
+}
+
+/// Compare two sets of password rules, returning every field that isn't semantically equivalent.
+///
+/// Both sets of rules are compared with their defaults filled in (see
+/// [`Defaults::with_defaults`]), so rules that leave out `allowed` match rules that spell out the
+/// default, and with their `required` groups normalized (see
+/// [`PasswordRulesExt::normalize_required`]), so repeating a group doesn't count as a difference.
+/// `allowed` classes that are redundant because they're also required are ignored, and
+/// are left out of the values in the returned diffs.
+pub fn diff_rules(rules: &PasswordRules, other_rules: &PasswordRules) -> Vec<FieldDiff> {
+    diff_rules_with(rules, other_rules, &DiffOptions::default())
+}
+
+/// Compare two sets of password rules using `options`; see [`diff_rules`]
+pub fn diff_rules_with(
+    rules: &PasswordRules,
+    other_rules: &PasswordRules,
+    options: &DiffOptions,
+) -> Vec<FieldDiff> {
+    let rules = &rules.with_defaults().normalize_required();
+    let other_rules = &other_rules.with_defaults().normalize_required();
+    let mut diffs = Vec::new();
+
+    let numbers = [
+        ("min_length", rules.min_length, other_rules.min_length),
+        ("max_length", rules.max_length, other_rules.max_length),
+        (
+            "max_consecutive",
+            rules.max_consecutive,
+            other_rules.max_consecutive,
+        ),
+    ];
+    for (field, left, right) in numbers.iter() {
+        if left != right {
+            diffs.push(FieldDiff {
+                field,
+                left: format_number(*left),
+                right: format_number(*right),
+            });
+        }
+    }
+
+    let (allowed, other_allowed) = if options.strict_allowed {
+        (rules.allowed.clone(), other_rules.allowed.clone())
+    } else {
+        (rules.necessary_allowed(), other_rules.necessary_allowed())
+    };
+    let allowed_differs = if options.ordered_allowed {
+        allowed != other_allowed
+    } else {
+        canonical_classes(&allowed) != canonical_classes(&other_allowed)
+    };
+    if allowed_differs {
+        diffs.push(FieldDiff {
+            field: "allowed",
+            left: syntax::format_classes(&allowed),
+            right: syntax::format_classes(&other_allowed),
+        });
+    }
+
+    if !required_equivalent(&rules.required, &other_rules.required) {
+        diffs.push(FieldDiff {
+            field: "required",
+            left: format_required(&rules.required),
+            right: format_required(&other_rules.required),
+        });
+    }
+
+    diffs
+}
+
+/// Extension methods for comparing `PasswordRules`
+pub trait SemanticEq {
+    /// Whether two sets of rules mean the same thing.
+    ///
+    /// The numeric properties are compared directly, while `allowed` and the `required` groups
+    /// are compared as sets of canonicalized classes, ignoring `allowed` classes that are also
+    /// required. See [`diff_rules`] for the fields that differ.
+    fn semantically_eq(&self, other: &Self) -> bool;
+}
+
+impl SemanticEq for PasswordRules {
+    fn semantically_eq(&self, other: &Self) -> bool {
+        diff_rules(self, other).is_empty()
+    }
+}
+
+/// The rules in a canonical form, so that two sets of rules are semantically equal (see
+/// [`SemanticEq`]) exactly when their canonical forms are equal.
+///
+/// The defaults are filled in, `allowed` classes that are also required are dropped, and the
+/// classes in `allowed` and in each `required` group are canonicalized and sorted, as are the
+/// `required` groups themselves, with duplicates removed.
+pub fn canonical_rules(rules: &PasswordRules) -> PasswordRules {
+    let rules = rules.with_defaults().normalize_required();
+    let mut required = rules.required.clone();
+    required.sort();
+
+    PasswordRules {
+        allowed: canonical_classes(&rules.necessary_allowed()),
+        required,
+        ..rules
+    }
+}
+
+/// `PasswordRules` that can be hashed and compared by what they mean, such as to group
+/// equivalent rules with a `HashMap`.
+///
+/// `PasswordRules` belongs to the parser, so it can't implement `Hash` here. The rules are
+/// converted to their [`canonical_rules`] when the key is made, so keys are equal, and hash
+/// equally, exactly when the rules are [`SemanticEq::semantically_eq`].
+///
+/// ```
+/// use password_rules_checker::diff::SemanticKey;
+/// use password_rules_parser::parse_password_rules;
+/// use std::collections::HashSet;
+///
+/// let keys: HashSet<SemanticKey> = [
+///     "required: upper; required: digit;",
+///     "required: digit; required: upper; required: digit;",
+///     "required: upper;",
+/// ]
+/// .iter()
+/// .map(|rules| SemanticKey::new(&parse_password_rules(rules, true).unwrap()))
+/// .collect();
+///
+/// assert_eq!(keys.len(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SemanticKey(PasswordRules);
+
+impl SemanticKey {
+    pub fn new(rules: &PasswordRules) -> Self {
+        SemanticKey(canonical_rules(rules))
+    }
+
+    /// The canonical form of the rules
+    pub fn rules(&self) -> &PasswordRules {
+        &self.0
+    }
+}
+
+impl Hash for SemanticKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.min_length.hash(state);
+        self.0.max_length.hash(state);
+        self.0.max_consecutive.hash(state);
+
+        self.0.allowed.len().hash(state);
+        for class in self.0.allowed.iter() {
+            hash_class(class, state);
+        }
+        self.0.required.len().hash(state);
+        for classes in self.0.required.iter() {
+            classes.len().hash(state);
+            for class in classes.iter() {
+                hash_class(class, state);
+            }
+        }
+    }
+}
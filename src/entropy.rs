@@ -0,0 +1,59 @@
+//! Rough estimates of the password strength that a set of rules forces
+
+use crate::class::CharacterClassExt;
+use password_rules_parser::{CharacterClass, PasswordRules};
+
+/// The characters a class is counted as having.
+///
+/// `unicode` is counted as `ascii-printable`, which is a lower bound on what it allows.
+fn class_chars(class: &CharacterClass) -> Vec<char> {
+    class
+        .members()
+        .unwrap_or_else(|| CharacterClass::AsciiPrintable.chars())
+}
+
+/// The smallest class in `classes`, if there are any
+fn smallest_class(classes: &[CharacterClass]) -> Option<&CharacterClass> {
+    classes.iter().min_by_key(|class| class_chars(class).len())
+}
+
+/// Extension methods for estimating the strength of passwords that satisfy `PasswordRules`
+pub trait Entropy {
+    /// A rough lower bound, in bits, on the entropy of a password that satisfies the rules.
+    ///
+    /// This models the weakest password someone could choose while still following the rules:
+    /// one that is exactly `min_length` characters long (or as many characters as there are
+    /// `required` groups, if that's more) and that only uses characters from the smallest class
+    /// of each `required` group. If nothing is required, the smallest `allowed` class (or
+    /// `ascii-printable`, if nothing is allowed either) is used instead. Each character is then
+    /// assumed to be chosen uniformly at random from that alphabet, giving
+    /// `length * log2(alphabet size)` bits.
+    ///
+    /// Real passwords are rarely chosen uniformly, and `max-consecutive` is ignored, so this is
+    /// only useful for comparing rules with one another. Rules with no `min_length` force no
+    /// strength at all and estimate 0 bits unless they require some characters.
+    fn min_entropy_bits(&self) -> f64;
+}
+
+impl Entropy for PasswordRules {
+    fn min_entropy_bits(&self) -> f64 {
+        let length = self.min_length.unwrap_or(0).max(self.required.len() as u32);
+
+        let mut alphabet: Vec<char> = if self.required.is_empty() {
+            class_chars(smallest_class(&self.allowed).unwrap_or(&CharacterClass::AsciiPrintable))
+        } else {
+            self.required
+                .iter()
+                .filter_map(|classes| smallest_class(classes))
+                .flat_map(class_chars)
+                .collect()
+        };
+        alphabet.sort_unstable();
+        alphabet.dedup();
+
+        if alphabet.is_empty() {
+            return 0.0;
+        }
+        f64::from(length) * (alphabet.len() as f64).log2()
+    }
+}
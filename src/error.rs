@@ -0,0 +1,230 @@
+//! Extension methods for the parser's `PasswordRulesError`
+
+use crate::syntax;
+use password_rules_parser::error::{Expected, PasswordRulesError};
+use serde::Serialize;
+use std::fmt::{self, Display, Formatter};
+
+/// The location of a parse error in a rules string
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Span {
+    /// The byte offset of the start of the offending token
+    pub start: usize,
+    /// The byte offset just past the end of the offending token; this is equal to `start` if the
+    /// error is at the end of the input
+    pub end: usize,
+    /// The 1-based line of `start`
+    pub line: u32,
+    /// The 1-based column of `start`, counted in characters
+    pub column: u32,
+}
+
+/// The 1-based line and column of the byte at `index` in `s`, with the column counted in
+/// characters
+pub(crate) fn location(s: &str, index: usize) -> (u32, u32) {
+    let prefix = &s[..index];
+    let line = prefix.chars().filter(|&c| c == '\n').count() + 1;
+    let line_start = prefix.rfind('\n').map_or(0, |i| i + 1);
+    let column = prefix[line_start..].chars().count() + 1;
+    (line as u32, column as u32)
+}
+
+/// The byte offset in `source` of an index reported by the parser, which is relative to its
+/// trimmed copy of the input
+fn source_index(source: &str, index: usize) -> usize {
+    (source.len() - source.trim_start().len() + index).min(source.len())
+}
+
+/// Extension methods for `PasswordRulesError`
+pub trait ErrorSpan {
+    /// The span of the token in `source` (the string that failed to parse) where parsing failed.
+    ///
+    /// The parser reports the position it expected something else at, which is the same position
+    /// its pretty-printed errors point to; the span extends from there to the end of the token
+    /// found instead (up to the next whitespace, `,`, or `;`). Returns `None` if the error has no
+    /// location, which is the case for empty input.
+    fn span(&self, source: &str) -> Option<Span>;
+
+    /// Correct the lines and columns of the error so that they're relative to `source` (the
+    /// string that failed to parse) and count characters rather than bytes.
+    ///
+    /// The parser counts columns in bytes, relative to its trimmed copy of the input, so its
+    /// pretty-printed errors point at the wrong character when the input has leading whitespace
+    /// or non-ASCII characters before the error on the same line. Call this before
+    /// `to_string_pretty` to have the caret land on the offending character.
+    fn locate_in(&mut self, source: &str);
+
+    /// Like `to_string_pretty`, but without any ANSI color codes, for writing errors somewhere
+    /// other than a terminal.
+    ///
+    /// `to_string_pretty` colors its output unless coloring has been turned off globally (with
+    /// `colored::control::set_override`); this never colors it, with the same annotated,
+    /// caret-pointed text either way.
+    ///
+    /// ```
+    /// use password_rules_checker::ErrorSpan;
+    /// use password_rules_parser::parse_password_rules;
+    ///
+    /// let source = "minlength: eight;";
+    /// let e = parse_password_rules(source, false).unwrap_err();
+    /// let plain = e.to_string_plain(source).unwrap();
+    ///
+    /// assert!(plain.contains("minlength: eight;"));
+    /// assert!(!plain.contains('\x1b'));
+    /// ```
+    fn to_string_plain(&self, source: &str) -> Result<String, fmt::Error>;
+}
+
+/// `s` with its ANSI escape sequences (such as color codes) removed
+fn strip_ansi(s: &str) -> String {
+    let mut stripped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            stripped.push(c);
+            continue;
+        }
+        // A control sequence is `ESC [`, then parameters, then a final byte in `@`-`~`
+        if chars.next() == Some('[') {
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        }
+    }
+    stripped
+}
+
+impl ErrorSpan for PasswordRulesError {
+    fn span(&self, source: &str) -> Option<Span> {
+        let expected = self.expectations.iter().min_by_key(|e| e.index)?;
+
+        let start = source_index(source, expected.index);
+        let rest = &source[start..];
+        let token_len = match rest.find(|c: char| c.is_whitespace() || c == ',' || c == ';') {
+            // Always cover at least the character that was unexpected
+            Some(0) => rest.chars().next().map_or(0, char::len_utf8),
+            Some(len) => len,
+            None => rest.len(),
+        };
+
+        let (line, column) = location(source, start);
+        Some(Span {
+            start,
+            end: start + token_len,
+            line,
+            column,
+        })
+    }
+
+    fn locate_in(&mut self, source: &str) {
+        for expected in self.expectations.iter_mut() {
+            let (line, column) = location(source, source_index(source, expected.index));
+            expected.line = line;
+            expected.column = column;
+        }
+    }
+
+    fn to_string_plain(&self, source: &str) -> Result<String, fmt::Error> {
+        self.to_string_pretty(source)
+            .map(|pretty| strip_ansi(&pretty))
+    }
+}
+
+/// The broad kind of problem that made parsing fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ErrorKind {
+    /// The input was empty (or only whitespace)
+    EmptyInput,
+    /// A clause didn't start with a property the parser understands
+    UnknownProperty,
+    /// A property that takes a number was given something else
+    InvalidNumber,
+    /// A property that takes character classes was given something that isn't one
+    UnknownClass,
+    /// A custom character class was missing its closing `]`
+    UnterminatedClass,
+    /// A property name wasn't followed by a `:`
+    MissingColon,
+    /// A `\` in a custom class was followed by a character that can't be escaped (see
+    /// [`syntax::expand_escapes`])
+    InvalidEscape,
+    /// A range in a custom class runs backwards, such as `[z-a]` (see [`syntax::expand_ranges`])
+    InvalidRange,
+    /// Something other than a `,` or `;` followed a complete value, or the input ended early
+    UnexpectedToken,
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorKind::EmptyInput => write!(f, "empty-input"),
+            ErrorKind::UnknownProperty => write!(f, "unknown-property"),
+            ErrorKind::InvalidNumber => write!(f, "invalid-number"),
+            ErrorKind::UnknownClass => write!(f, "unknown-class"),
+            ErrorKind::UnterminatedClass => write!(f, "unterminated-class"),
+            ErrorKind::MissingColon => write!(f, "missing-colon"),
+            ErrorKind::InvalidEscape => write!(f, "invalid-escape"),
+            ErrorKind::InvalidRange => write!(f, "invalid-range"),
+            ErrorKind::UnexpectedToken => write!(f, "unexpected-token"),
+        }
+    }
+}
+
+/// Extension methods for inspecting `PasswordRulesError`
+pub trait PasswordRulesErrorExt {
+    /// The kind of problem that made parsing fail, worked out from what the parser expected to
+    /// find where it failed.
+    ///
+    /// ```
+    /// use password_rules_checker::error::{ErrorKind, PasswordRulesErrorExt};
+    /// use password_rules_parser::parse_password_rules;
+    ///
+    /// let e = parse_password_rules("minlength: eight;", false).unwrap_err();
+    ///
+    /// assert_eq!(e.kind(), ErrorKind::InvalidNumber);
+    /// ```
+    fn kind(&self) -> ErrorKind;
+}
+
+impl PasswordRulesErrorExt for PasswordRulesError {
+    fn kind(&self) -> ErrorKind {
+        // The expectations are sorted by index, and only the ones at the failure matter
+        let index = match self.expectations.first() {
+            Some(expected) => expected.index,
+            None => return ErrorKind::EmptyInput,
+        };
+        let expected: Vec<Expected> = self
+            .expectations
+            .iter()
+            .filter(|e| e.index == index)
+            .map(|e| e.expected)
+            .collect();
+        let expects = |f: fn(&Expected) -> bool| expected.iter().any(f);
+
+        // The escape error expects a `]` too, so it has to be told apart from an unterminated
+        // class first
+        if expects(|e| matches!(e, Expected::Char('\\'))) {
+            ErrorKind::InvalidEscape
+        } else if expects(|e| matches!(e, Expected::Tag(tag) if *tag == syntax::ASCENDING_RANGE)) {
+            ErrorKind::InvalidRange
+        } else if expects(|e| matches!(e, Expected::Number)) {
+            ErrorKind::InvalidNumber
+        } else if expects(|e| matches!(e, Expected::Char(']'))) {
+            ErrorKind::UnterminatedClass
+        } else if expects(|e| matches!(e, Expected::Tag(tag) if syntax::PROPERTIES.contains(tag))) {
+            ErrorKind::UnknownProperty
+        } else if expects(|e| {
+            matches!(e, Expected::Tag(tag) if syntax::CLASS_TOKENS.contains(tag))
+                || matches!(e, Expected::Char('['))
+        }) {
+            ErrorKind::UnknownClass
+        } else if expects(|e| matches!(e, Expected::Char(':'))) {
+            ErrorKind::MissingColon
+        } else {
+            ErrorKind::UnexpectedToken
+        }
+    }
+}
@@ -0,0 +1,481 @@
+//! Generating random passwords that satisfy parsed password rules
+
+use crate::class::CharacterClassExt;
+use crate::rules::PasswordRulesExt;
+use crate::syntax;
+use crate::validate::{Validate, ValidationReport, Violation};
+use password_rules_parser::{CharacterClass, PasswordRules};
+use rand::seq::{index, SliceRandom};
+use rand::Rng;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// How many times generation is attempted before giving up on satisfying `max_consecutive`
+const MAX_ATTEMPTS: usize = 100;
+
+/// The longest password that's ever generated. Rules with a longer `max_length` get passwords no
+/// longer than this, and rules with a longer `min_length` can't have passwords generated at all,
+/// so that a rule like `maxlength: 4000000000;` can't make the generator run out of memory.
+pub const MAX_GENERATED_LENGTH: u32 = 4096;
+
+/// The non-ASCII characters that [`GenerateOptions::default`] draws from for `unicode`, on top
+/// of the `ascii-printable` characters: accented Latin letters and common symbols that most
+/// keyboard layouts can type
+const COMMON_NON_ASCII: &str =
+    "àáâäãåæçèéêëìíîïñòóôöõøœßùúûüýÿÀÁÂÄÃÅÆÇÈÉÊËÌÍÎÏÑÒÓÔÖÕØŒÙÚÛÜÝ€£¥§°±µ¿¡";
+
+/// How long the passwords generated for a set of rules are
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GenerateMode {
+    /// A length picked uniformly from the lengths the rules allow
+    #[default]
+    Random,
+    /// The longest length the rules allow, which gives the strongest passwords the rules permit,
+    /// since every character is drawn uniformly from every permitted character
+    Strongest,
+    /// The shortest length the rules allow, so the passwords only just satisfy the rules
+    Minimal,
+}
+
+impl Display for GenerateMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            GenerateMode::Random => write!(f, "random"),
+            GenerateMode::Strongest => write!(f, "strongest"),
+            GenerateMode::Minimal => write!(f, "minimal"),
+        }
+    }
+}
+
+/// The error returned when parsing an unknown generate mode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownGenerateMode(pub String);
+
+impl Display for UnknownGenerateMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Unknown generate mode `{}`; expected `random`, `strongest`, or `minimal`",
+            self.0
+        )
+    }
+}
+
+impl Error for UnknownGenerateMode {}
+
+impl FromStr for GenerateMode {
+    type Err = UnknownGenerateMode;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "random" => Ok(GenerateMode::Random),
+            "strongest" => Ok(GenerateMode::Strongest),
+            "minimal" => Ok(GenerateMode::Minimal),
+            _ => Err(UnknownGenerateMode(s.to_string())),
+        }
+    }
+}
+
+/// Options that control how passwords are generated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenerateOptions {
+    /// The characters drawn from for the `unicode` class, which is too large to draw from
+    /// directly. Defaults to the `ascii-printable` characters plus a selection of common
+    /// accented letters and symbols. This shouldn't be empty.
+    pub unicode_pool: Vec<char>,
+    /// The longest password to generate when the rules don't set `max_length` (or the
+    /// `min_length`, if that's longer). Defaults to 20.
+    pub default_max_length: u32,
+    /// How long to make the passwords. Defaults to [`GenerateMode::Random`].
+    pub mode: GenerateMode,
+    /// Validate every generated password against the rules before returning it, returning
+    /// [`GenerateError::InvalidPassword`] if it doesn't satisfy them. The check is always made in
+    /// debug builds, where a password that fails it panics unless this is set, since it means
+    /// the generator has a bug. Defaults to `false`.
+    pub self_check: bool,
+}
+
+impl Default for GenerateOptions {
+    fn default() -> Self {
+        let mut unicode_pool = CharacterClass::AsciiPrintable.chars();
+        unicode_pool.extend(COMMON_NON_ASCII.chars());
+        Self {
+            unicode_pool,
+            default_max_length: 20,
+            mode: GenerateMode::default(),
+            self_check: false,
+        }
+    }
+}
+
+/// Reasons that a password can't be generated for a set of rules
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GenerateError {
+    /// `min_length` is greater than `max_length`
+    MinLengthExceedsMaxLength { min_length: u32, max_length: u32 },
+    /// `min_length` is longer than [`MAX_GENERATED_LENGTH`]
+    MinLengthTooLong { min_length: u32 },
+    /// There are more `required` groups than `max_length` has room for
+    TooManyRequired { required: usize, max_length: u32 },
+    /// A `required` group has no characters to draw from, such as `required: unicode;` when
+    /// [`GenerateOptions::unicode_pool`] is empty
+    EmptyRequiredGroup { classes: Vec<CharacterClass> },
+    /// No password could be found without repeating a character more than `max_consecutive`
+    /// times in a row
+    MaxConsecutiveUnsatisfiable { max_consecutive: u32 },
+    /// The generated password doesn't satisfy the rules, which is a bug in the generator (see
+    /// [`GenerateOptions::self_check`])
+    InvalidPassword {
+        password: String,
+        report: ValidationReport,
+    },
+}
+
+impl Display for GenerateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            GenerateError::MinLengthExceedsMaxLength {
+                min_length,
+                max_length,
+            } => write!(
+                f,
+                "the minimum length ({}) is greater than the maximum length ({})",
+                min_length, max_length
+            ),
+            GenerateError::MinLengthTooLong { min_length } => write!(
+                f,
+                "the minimum length ({}) is longer than the longest password that can be \
+                 generated ({})",
+                min_length, MAX_GENERATED_LENGTH
+            ),
+            GenerateError::TooManyRequired {
+                required,
+                max_length,
+            } => write!(
+                f,
+                "{} required character classes don't fit within the maximum length ({})",
+                required, max_length
+            ),
+            GenerateError::EmptyRequiredGroup { classes } => write!(
+                f,
+                "the required character classes `{}` have no characters to draw from",
+                syntax::format_classes(classes)
+            ),
+            GenerateError::MaxConsecutiveUnsatisfiable { max_consecutive } => write!(
+                f,
+                "couldn't avoid repeating a character more than {} times in a row",
+                max_consecutive
+            ),
+            GenerateError::InvalidPassword { password, report } => write!(
+                f,
+                "the generated password {:?} doesn't satisfy the rules: {}",
+                password,
+                report
+                    .violations
+                    .iter()
+                    .map(|violation| violation.to_string())
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            ),
+        }
+    }
+}
+
+impl Error for GenerateError {}
+
+/// Every character in any of `classes`, without duplicates. `unicode` contributes the characters
+/// in `unicode_pool`.
+fn pool<'a>(
+    classes: impl IntoIterator<Item = &'a CharacterClass>,
+    unicode_pool: &[char],
+) -> Vec<char> {
+    let mut chars: Vec<char> = classes
+        .into_iter()
+        .flat_map(|class| class.members().unwrap_or_else(|| unicode_pool.to_vec()))
+        .collect();
+    chars.sort_unstable();
+    chars.dedup();
+    chars
+}
+
+/// Fill each slot with a character drawn from its pool, never repeating a character more than
+/// `max_consecutive` times in a row.
+///
+/// Returns `None` if a slot's pool only contains the character that can't be repeated again.
+fn fill_slots<R: Rng + ?Sized>(
+    slots: &[&[char]],
+    max_consecutive: Option<u32>,
+    rng: &mut R,
+) -> Option<String> {
+    let mut password = String::with_capacity(slots.len());
+    let mut previous = None;
+    let mut run = 0;
+
+    for pool in slots.iter() {
+        // The character that would make the trailing run too long, if there is one
+        let banned = previous.filter(|_| max_consecutive.is_some_and(|max| run >= max as usize));
+        if pool.iter().all(|&c| Some(c) == banned) {
+            return None;
+        }
+
+        let c = loop {
+            let c = *pool.choose(rng)?;
+            if Some(c) != banned {
+                break c;
+            }
+        };
+
+        if Some(c) == previous {
+            run += 1;
+        } else {
+            previous = Some(c);
+            run = 1;
+        }
+        password.push(c);
+    }
+
+    Some(password)
+}
+
+/// A random string of `length` characters drawn from `pool`, which must not be empty
+fn random_string<R: Rng + ?Sized>(pool: &[char], length: usize, rng: &mut R) -> String {
+    (0..length).filter_map(|_| pool.choose(rng)).collect()
+}
+
+/// Validate a generated password against `rules` if `options` asks for it, or in debug builds
+fn check_generated(
+    rules: &PasswordRules,
+    password: String,
+    options: &GenerateOptions,
+) -> Result<String, GenerateError> {
+    if options.self_check || cfg!(debug_assertions) {
+        let report = rules.check(&password);
+        if !report.is_valid() {
+            // Unless the caller is checking for it, an invalid password is a bug worth crashing
+            // over while debugging
+            assert!(
+                options.self_check,
+                "generated {:?}, which doesn't satisfy the rules: {}",
+                password, report
+            );
+            return Err(GenerateError::InvalidPassword { password, report });
+        }
+    }
+
+    Ok(password)
+}
+
+/// Whether a violation is the kind that a failing password was generated to cause
+type IsIntended = fn(&Violation) -> bool;
+
+/// Characters that aren't in `ascii-printable`, for breaking rules that allow all of it
+const NON_ASCII_CHARS: &[char] = &['é', '€', '\u{1f511}'];
+
+/// Extension methods for generating passwords from `PasswordRules`
+pub trait Generate {
+    /// Generate a random password that satisfies the rules.
+    ///
+    /// The length is picked uniformly between `min_length` and `max_length`, inclusive; when
+    /// there's no maximum length, passwords are at most 20 characters long (see
+    /// [`GenerateOptions::default_max_length`]), or `min_length` if that's longer. Passwords are
+    /// never empty unless `max_length` is 0, and are long enough for a character from every
+    /// `required` group; rules whose `min_length` is greater than their `max_length`, or with more
+    /// `required` groups than fit in `max_length`, are an error. Passwords are also never longer
+    /// than [`MAX_GENERATED_LENGTH`], whatever `max_length` is, and rules whose `min_length` is
+    /// longer than that are an error too. At least one character is drawn from every `required`
+    /// group, and the rest are drawn from the `allowed` and `required` classes (or
+    /// `ascii-printable` if there are neither). Characters are re-drawn whenever they would
+    /// repeat a character more than `max_consecutive` times in a row.
+    ///
+    /// `unicode` is drawn from the default [`GenerateOptions::unicode_pool`], so passwords for
+    /// rules that allow `unicode` only ever contain ASCII and a small set of common non-ASCII
+    /// characters.
+    fn generate<R: Rng + ?Sized>(&self, rng: &mut R) -> Result<String, GenerateError> {
+        self.generate_with(&GenerateOptions::default(), rng)
+    }
+
+    /// Generate a random password that satisfies the rules, using `options`; see
+    /// [`Generate::generate`].
+    ///
+    /// With [`GenerateMode::Strongest`] or [`GenerateMode::Minimal`], the length is the longest or
+    /// shortest of the lengths that would otherwise be picked from.
+    fn generate_with<R: Rng + ?Sized>(
+        &self,
+        options: &GenerateOptions,
+        rng: &mut R,
+    ) -> Result<String, GenerateError>;
+
+    /// Generate a random password that violates the rules, along with the violation it was
+    /// generated to cause (as reported by [`Validate::check`]).
+    ///
+    /// Which rule is broken is picked at random from the ones that can be: the password may be
+    /// too short or too long, leave out a `required` group, contain a character that isn't
+    /// allowed, or repeat a character too many times in a row. The password may happen to break
+    /// other rules as well. Passwords are never longer than [`MAX_GENERATED_LENGTH`], so a limit
+    /// that can only be broken by a longer password isn't broken. Returns `None` if the rules
+    /// can't be broken at all, such as `allowed: unicode;` with no other properties.
+    fn generate_failing<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<(String, Violation)>;
+}
+
+impl Generate for PasswordRules {
+    fn generate_with<R: Rng + ?Sized>(
+        &self,
+        options: &GenerateOptions,
+        rng: &mut R,
+    ) -> Result<String, GenerateError> {
+        let min_length = self.min_length.unwrap_or(0);
+        let max_length = self
+            .max_length
+            .unwrap_or_else(|| min_length.max(options.default_max_length));
+        if min_length > max_length {
+            return Err(GenerateError::MinLengthExceedsMaxLength {
+                min_length,
+                max_length,
+            });
+        }
+        if min_length > MAX_GENERATED_LENGTH {
+            return Err(GenerateError::MinLengthTooLong { min_length });
+        }
+        let max_length = max_length.min(MAX_GENERATED_LENGTH);
+        if self.required.len() as u64 > max_length.into() {
+            return Err(GenerateError::TooManyRequired {
+                required: self.required.len(),
+                max_length,
+            });
+        }
+
+        let mut shortest = min_length.max(self.required.len() as u32);
+        if shortest == 0 {
+            shortest = max_length.min(1);
+        }
+
+        let unicode_pool = &options.unicode_pool;
+        let required_pools: Vec<Vec<char>> = self
+            .required
+            .iter()
+            .map(|classes| pool(classes, unicode_pool))
+            .collect();
+        if let Some(empty) = required_pools.iter().position(Vec::is_empty) {
+            return Err(GenerateError::EmptyRequiredGroup {
+                classes: self.required[empty].clone(),
+            });
+        }
+        let mut permitted = pool(
+            self.allowed.iter().chain(self.required_classes()),
+            unicode_pool,
+        );
+        if permitted.is_empty() {
+            permitted = CharacterClass::AsciiPrintable.chars();
+        }
+
+        if self.max_consecutive == Some(0) && shortest > 0 {
+            return Err(GenerateError::MaxConsecutiveUnsatisfiable { max_consecutive: 0 });
+        }
+
+        for _ in 0..MAX_ATTEMPTS {
+            let length = match options.mode {
+                GenerateMode::Random => rng.gen_range(shortest..=max_length),
+                GenerateMode::Strongest => max_length,
+                GenerateMode::Minimal => shortest,
+            } as usize;
+
+            // Pick a distinct position for a character from each required group; every other
+            // position can hold any permitted character. This is the same as drawing a character
+            // for each group and shuffling them in among the rest, but lets `fill_slots` respect
+            // `max_consecutive` as it goes rather than after the fact.
+            let mut slots: Vec<&[char]> = vec![&permitted; length];
+            let positions = index::sample(rng, length, required_pools.len());
+            for (position, required_pool) in positions.iter().zip(required_pools.iter()) {
+                slots[position] = required_pool;
+            }
+
+            if let Some(password) = fill_slots(&slots, self.max_consecutive, rng) {
+                return check_generated(self, password, options);
+            }
+        }
+
+        Err(GenerateError::MaxConsecutiveUnsatisfiable {
+            // Every pool has characters to draw from, so the loop can only fail if
+            // `max_consecutive` is set
+            max_consecutive: self.max_consecutive.unwrap_or_default(),
+        })
+    }
+
+    fn generate_failing<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<(String, Violation)> {
+        let options = GenerateOptions::default();
+        let classes: Vec<&CharacterClass> =
+            self.allowed.iter().chain(self.required_classes()).collect();
+        let mut permitted = pool(classes.iter().copied(), &options.unicode_pool);
+        if permitted.is_empty() {
+            permitted = CharacterClass::AsciiPrintable.chars();
+        }
+
+        // A length that doesn't break the length limits on its own, unless they're beyond
+        // `MAX_GENERATED_LENGTH`. Candidates that would have to be longer than that are left out.
+        let limit = MAX_GENERATED_LENGTH as usize;
+        let min_length = self.min_length.unwrap_or(0) as usize;
+        let mut length = min_length.max(self.required.len()).max(1);
+        if let Some(max_length) = self.max_length {
+            length = length.min(max_length as usize);
+        }
+        let length = length.min(limit);
+
+        // Each candidate is paired with the kind of violation it's meant to cause
+        let mut candidates: Vec<(String, IsIntended)> = Vec::new();
+        if min_length > 0 && min_length <= limit + 1 {
+            candidates.push((random_string(&permitted, min_length - 1, rng), |v| {
+                matches!(v, Violation::TooShort { .. })
+            }));
+        }
+        if let Some(max_length) = self.max_length.filter(|&max| (max as usize) < limit) {
+            candidates.push((
+                random_string(&permitted, max_length as usize + 1, rng),
+                |v| matches!(v, Violation::TooLong { .. }),
+            ));
+        }
+        for classes in self.required.iter() {
+            let without_group: Vec<char> = permitted
+                .iter()
+                .copied()
+                .filter(|&c| !classes.iter().any(|class| class.contains(c)))
+                .collect();
+            if !without_group.is_empty() {
+                candidates.push((random_string(&without_group, length, rng), |v| {
+                    matches!(v, Violation::MissingRequired(_))
+                }));
+            }
+        }
+        let disallowed = CharacterClass::AsciiPrintable
+            .chars()
+            .into_iter()
+            .chain(NON_ASCII_CHARS.iter().copied())
+            .find(|&c| !classes.iter().any(|class| class.contains(c)));
+        if let Some(disallowed) = disallowed {
+            let mut chars: Vec<char> = random_string(&permitted, length, rng).chars().collect();
+            match chars.choose_mut(rng) {
+                Some(c) => *c = disallowed,
+                None => chars.push(disallowed),
+            }
+            candidates.push((chars.into_iter().collect(), |v| {
+                matches!(v, Violation::DisallowedCharacter(_))
+            }));
+        }
+        let max_consecutive = self.max_consecutive.filter(|&max| (max as usize) < limit);
+        if let (Some(max_consecutive), Some(&c)) = (max_consecutive, permitted.choose(rng)) {
+            candidates.push((c.to_string().repeat(max_consecutive as usize + 1), |v| {
+                matches!(v, Violation::TooManyConsecutive { .. })
+            }));
+        }
+
+        candidates.shuffle(rng);
+        candidates.into_iter().find_map(|(password, is_intended)| {
+            let violation = self
+                .check(&password)
+                .violations
+                .into_iter()
+                .find(is_intended)?;
+            Some((password, violation))
+        })
+    }
+}
@@ -0,0 +1,60 @@
+//! Tools for auditing password rules written in Apple's [password rules language], built on top of
+//! 1Password's [`password-rules-parser` crate][parser].
+//!
+//! The parser produces a [`PasswordRules`](password_rules_parser::PasswordRules) struct; this
+//! crate adds extension traits that make the parsed rules useful for more than just parsing.
+//!
+//! ```
+//! use password_rules_checker::Validate;
+//! use password_rules_parser::parse_password_rules;
+//!
+//! let rules = parse_password_rules("minlength: 8; required: digit; allowed: lower;", true).unwrap();
+//!
+//! assert!(rules.validate("hunter22"));
+//! assert!(!rules.validate("hunter"));
+//! ```
+//!
+//! This crate requires `std`. The parser itself lives in the `password-rules-parser` crate, which
+//! depends on `std` (its errors and pretty-printing use `std` types), so `no_std` support has to
+//! start there; the types it provides can't be constructed here without it.
+//!
+//! [password rules language]: https://developer.apple.com/password-rules/
+//! [parser]: https://github.com/1Password/password-rules-parser
+
+pub mod builder;
+pub mod class;
+pub mod defaults;
+pub mod describe;
+pub mod diff;
+pub mod entropy;
+pub mod error;
+pub mod generate;
+pub mod lint;
+pub mod merge;
+pub mod onepassword;
+pub mod quirks;
+pub mod recover;
+pub mod rules;
+#[cfg(feature = "serde-rules")]
+pub mod serde_rules;
+pub mod stats;
+pub mod syntax;
+pub mod validate;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use builder::PasswordRulesBuilder;
+pub use class::{CharacterClassExt, ClassKey};
+pub use defaults::Defaults;
+pub use describe::Describe;
+pub use diff::{DiffOptions, SemanticEq, SemanticKey};
+pub use entropy::Entropy;
+pub use error::{ErrorKind, ErrorSpan, PasswordRulesErrorExt, Span};
+pub use generate::{Generate, GenerateError, GenerateMode, GenerateOptions, MAX_GENERATED_LENGTH};
+pub use lint::{Finding, Lint, Severity, Unsatisfiable};
+pub use merge::Merge;
+pub use onepassword::ToOnePassword;
+pub use recover::{parse_password_rules_lenient, parse_password_rules_recover};
+pub use rules::PasswordRulesExt;
+pub use syntax::ToRulesString;
+pub use validate::{Validate, ValidationReport, Violation};
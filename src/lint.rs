@@ -0,0 +1,745 @@
+//! Finding problems with password rules that parse successfully
+
+use crate::class::CharacterClassExt;
+use crate::rules::PasswordRulesExt;
+use crate::syntax;
+use password_rules_parser::{CharacterClass, PasswordRules};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// How serious a lint finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Something that might be worth improving
+    Info,
+    /// Something that's probably a mistake
+    Warning,
+    /// Something that's definitely broken
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Info => write!(f, "info"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// The error returned when parsing an unknown severity
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownSeverity(pub String);
+
+impl Display for UnknownSeverity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Unknown severity `{}`; expected `info`, `warning`, or `error`",
+            self.0
+        )
+    }
+}
+
+impl Error for UnknownSeverity {}
+
+impl FromStr for Severity {
+    type Err = UnknownSeverity;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "info" => Ok(Severity::Info),
+            "warning" => Ok(Severity::Warning),
+            "error" => Ok(Severity::Error),
+            _ => Err(UnknownSeverity(s.to_string())),
+        }
+    }
+}
+
+/// A single problem found by a lint
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    /// The name of the lint that produced the finding, such as `redundant-allowed`
+    pub lint: &'static str,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// The name of every lint, including the ones that work on rules strings and site keys rather
+/// than on parsed rules
+pub const LINTS: &[&str] = &[
+    "unsatisfiable",
+    "redundant-allowed",
+    "subsumed-required",
+    "pointless-required",
+    "missing-maxlength",
+    "missing-max-consecutive",
+    "unknown-property",
+    "empty-clause",
+    "non-canonical-whitespace",
+    "duplicate-class-character",
+    "invalid-site-key",
+];
+
+/// How a lint is configured by a [`LintConfig`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintLevel {
+    /// Don't report the lint's findings
+    Allow,
+    /// Report the lint's findings as info
+    Info,
+    /// Report the lint's findings as warnings
+    Warning,
+    /// Report the lint's findings as errors
+    Error,
+}
+
+/// Which lints are reported, and how seriously, such as from a `--lint-config` file.
+///
+/// Lints that aren't configured are reported at their usual severity, so the default config
+/// changes nothing.
+///
+/// ```
+/// use password_rules_checker::lint::{LintConfig, LintLevel};
+///
+/// let config: LintConfig = toml::from_str(
+///     r#"
+///     fail-on = "warning"
+///
+///     [lints]
+///     missing-maxlength = "allow"
+///     redundant-allowed = "error"
+///     "#,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(config.lints["missing-maxlength"], LintLevel::Allow);
+/// assert!(config.unknown_lints().is_empty());
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct LintConfig {
+    /// The severity of findings that should make linting fail, if it's configured
+    pub fail_on: Option<Severity>,
+    /// The configured lints, by name
+    #[serde(default)]
+    pub lints: BTreeMap<String, LintLevel>,
+}
+
+impl LintConfig {
+    /// The configured lints that aren't in [`LINTS`], which are probably misspelled
+    pub fn unknown_lints(&self) -> Vec<&str> {
+        self.lints
+            .keys()
+            .map(String::as_str)
+            .filter(|name| !LINTS.contains(name))
+            .collect()
+    }
+
+    /// Drop the findings of allowed lints and change the severity of the rest as configured
+    pub fn apply(&self, findings: &mut Vec<Finding>) {
+        findings.retain_mut(|finding| match self.lints.get(finding.lint) {
+            Some(LintLevel::Allow) => false,
+            Some(LintLevel::Info) => {
+                finding.severity = Severity::Info;
+                true
+            }
+            Some(LintLevel::Warning) => {
+                finding.severity = Severity::Warning;
+                true
+            }
+            Some(LintLevel::Error) => {
+                finding.severity = Severity::Error;
+                true
+            }
+            None => true,
+        });
+    }
+}
+
+/// A reason that no password can satisfy a set of rules
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Unsatisfiable {
+    /// `min_length` is greater than `max_length`
+    MinLengthExceedsMaxLength { min_length: u32, max_length: u32 },
+    /// More `required` groups have no characters in common with one another than there's room
+    /// for within `max_length`
+    TooManyRequired { required: usize, max_length: u32 },
+    /// A `required` group contains no characters at all, such as an empty custom class
+    EmptyRequired(Vec<CharacterClass>),
+    /// `max_consecutive` is 0, but the password can't be empty
+    MaxConsecutiveZero,
+    /// Only a single character is permitted, and reaching `min_length` would repeat it more than
+    /// `max_consecutive` times in a row
+    OnlyCharacterRepeated {
+        character: char,
+        min_length: u32,
+        max_consecutive: u32,
+    },
+}
+
+impl Display for Unsatisfiable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Unsatisfiable::MinLengthExceedsMaxLength {
+                min_length,
+                max_length,
+            } => write!(
+                f,
+                "`minlength` ({}) is greater than `maxlength` ({})",
+                min_length, max_length
+            ),
+            Unsatisfiable::TooManyRequired {
+                required,
+                max_length,
+            } => write!(
+                f,
+                "{} required classes with no characters in common don't fit within `maxlength` ({})",
+                required, max_length
+            ),
+            Unsatisfiable::EmptyRequired(classes) => write!(
+                f,
+                "the required classes `{}` don't contain any characters",
+                syntax::format_classes(classes)
+            ),
+            Unsatisfiable::MaxConsecutiveZero => {
+                write!(f, "`max-consecutive` is 0, so no characters can be used")
+            }
+            Unsatisfiable::OnlyCharacterRepeated {
+                character,
+                min_length,
+                max_consecutive,
+            } => write!(
+                f,
+                "{:?} is the only character that can be used, and `minlength` ({}) would repeat it \
+                more than `max-consecutive` ({}) times",
+                character, min_length, max_consecutive
+            ),
+        }
+    }
+}
+
+/// The characters in a group of classes, or `None` if the group includes `unicode`
+fn group_members(classes: &[CharacterClass]) -> Option<Vec<char>> {
+    let mut chars = Vec::new();
+    for class in classes.iter() {
+        chars.extend(class.members()?);
+    }
+    chars.sort_unstable();
+    chars.dedup();
+    Some(chars)
+}
+
+/// Whether every character in `group` is also in `other`, where `None` stands for a group that
+/// includes `unicode`
+fn group_is_subset(group: &Option<Vec<char>>, other: &Option<Vec<char>>) -> bool {
+    match (group, other) {
+        (_, None) => true,
+        (None, Some(_)) => false,
+        (Some(group), Some(other)) => group.iter().all(|c| other.contains(c)),
+    }
+}
+
+/// A lower bound on how many characters it takes to satisfy every group in `required`.
+///
+/// Groups that share no characters need a character each, so this counts a set of groups that
+/// are all disjoint from one another.
+fn disjoint_required(required: &[Vec<CharacterClass>]) -> usize {
+    let mut chosen: Vec<Vec<char>> = Vec::new();
+    for classes in required.iter() {
+        // A group containing `unicode` overlaps with everything
+        if let Some(members) = group_members(classes) {
+            if chosen
+                .iter()
+                .all(|other| !members.iter().any(|c| other.contains(c)))
+            {
+                chosen.push(members);
+            }
+        }
+    }
+    chosen.len()
+}
+
+/// Extension methods for finding problems with `PasswordRules`
+pub trait Lint {
+    /// The `allowed` classes that are redundant because they're also `required`, since required
+    /// characters are implicitly allowed
+    fn redundant_allowed(&self) -> Vec<CharacterClass>;
+
+    /// The `allowed` classes that aren't redundant; see [`Lint::redundant_allowed`]
+    fn necessary_allowed(&self) -> Vec<CharacterClass>;
+
+    /// What the `allowed` classes can be shortened to, or `None` if they can't be.
+    ///
+    /// This is [`Lint::necessary_allowed`], except that when the necessary classes and the
+    /// required classes together permit exactly the `ascii-printable` characters, it's
+    /// `ascii-printable`, which is how Apple usually writes such rules.
+    ///
+    /// ```
+    /// use password_rules_checker::Lint;
+    /// use password_rules_parser::{parse_password_rules, CharacterClass};
+    ///
+    /// let rules = parse_password_rules(
+    ///     "required: digit; required: special; allowed: upper, lower, digit;",
+    ///     false,
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(rules.shortened_allowed(), Some(vec![CharacterClass::AsciiPrintable]));
+    /// ```
+    fn shortened_allowed(&self) -> Option<Vec<CharacterClass>>;
+
+    /// Every `required` group that's redundant because another group is subsumed by it, paired
+    /// with the group that makes it redundant.
+    ///
+    /// A group is redundant if another group's characters are all in it, since any character
+    /// that satisfies the other group satisfies it too: in `required: digit; required: digit,
+    /// special;`, the second group is redundant because of the first. Of two groups with the
+    /// same characters, the later one is reported.
+    fn subsumed_required(&self) -> Vec<(Vec<CharacterClass>, Vec<CharacterClass>)>;
+
+    /// The `required` groups that every permitted character satisfies, so that any non-empty
+    /// password satisfies them.
+    ///
+    /// Characters from the `required` classes are implicitly allowed, so in `allowed: digit;
+    /// required: digit;` every character must be a digit, and requiring one adds nothing.
+    ///
+    /// ```
+    /// use password_rules_checker::Lint;
+    /// use password_rules_parser::{parse_password_rules, CharacterClass};
+    ///
+    /// let rules = parse_password_rules("allowed: digit; required: digit;", false).unwrap();
+    ///
+    /// assert_eq!(rules.pointless_required(), vec![vec![CharacterClass::Digit]]);
+    /// ```
+    fn pointless_required(&self) -> Vec<Vec<CharacterClass>>;
+
+    /// Every reason that no password can satisfy the rules.
+    ///
+    /// Characters from the `required` classes are implicitly allowed, so a required class that
+    /// isn't in `allowed` (as in `required: digit; allowed: lower;`) doesn't make the rules
+    /// unsatisfiable; a required group only does if it contains no characters at all.
+    fn unsatisfiable_reasons(&self) -> Vec<Unsatisfiable>;
+
+    /// Whether any password could satisfy the rules.
+    ///
+    /// The rules are unsatisfiable if (see [`Unsatisfiable`]):
+    ///
+    /// * `minlength` is greater than `maxlength`
+    /// * There are more `required` groups with no characters in common than `maxlength` has room
+    ///   for
+    /// * A `required` group contains no characters
+    /// * `max-consecutive` is 0 and the password can't be empty
+    /// * Only a single character is permitted, and `minlength` would repeat it more than
+    ///   `max-consecutive` times
+    ///
+    /// A `required` class that isn't `allowed` doesn't make the rules unsatisfiable, since
+    /// required characters are implicitly allowed.
+    ///
+    /// ```
+    /// use password_rules_checker::Lint;
+    /// use password_rules_parser::parse_password_rules;
+    ///
+    /// let rules = parse_password_rules("minlength: 12; maxlength: 8;", true).unwrap();
+    ///
+    /// assert!(!rules.is_satisfiable());
+    /// ```
+    fn is_satisfiable(&self) -> bool {
+        self.unsatisfiable_reasons().is_empty()
+    }
+
+    /// Run every lint over the rules.
+    ///
+    /// The lints are:
+    ///
+    /// * `unsatisfiable` (error): no password can satisfy the rules; see
+    ///   [`Lint::unsatisfiable_reasons`]
+    /// * `redundant-allowed` (warning): an `allowed` class is also required; see
+    ///   [`Lint::redundant_allowed`]
+    /// * `subsumed-required` (warning): a `required` group is made redundant by another; see
+    ///   [`Lint::subsumed_required`]
+    /// * `pointless-required` (warning): a `required` group is satisfied by every permitted
+    ///   character; see [`Lint::pointless_required`]
+    /// * `missing-maxlength` (warning): there's no `maxlength`, so passwords can be any length.
+    ///   Some backends silently truncate long passwords, so an unbounded maximum is worth
+    ///   reviewing, but plenty of sites leave it out on purpose
+    /// * `missing-max-consecutive` (info): there's no `max-consecutive`, so passwords like
+    ///   `aaaaaaaa` are accepted
+    fn findings(&self) -> Vec<Finding>;
+}
+
+impl Lint for PasswordRules {
+    fn redundant_allowed(&self) -> Vec<CharacterClass> {
+        self.allowed
+            .iter()
+            .filter(|allowed_class| {
+                self.required_classes()
+                    .any(|required_class| required_class == *allowed_class)
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn necessary_allowed(&self) -> Vec<CharacterClass> {
+        let redundant = self.redundant_allowed();
+        self.allowed
+            .iter()
+            .filter(|allowed_class| !redundant.contains(allowed_class))
+            .cloned()
+            .collect()
+    }
+
+    fn shortened_allowed(&self) -> Option<Vec<CharacterClass>> {
+        let necessary = self.necessary_allowed();
+        if necessary == self.allowed {
+            return None;
+        }
+
+        let permitted: Vec<CharacterClass> = necessary
+            .iter()
+            .chain(self.required_classes())
+            .cloned()
+            .collect();
+        if !necessary.is_empty()
+            && group_members(&permitted) == group_members(&[CharacterClass::AsciiPrintable])
+        {
+            return Some(vec![CharacterClass::AsciiPrintable]);
+        }
+
+        Some(necessary)
+    }
+
+    fn subsumed_required(&self) -> Vec<(Vec<CharacterClass>, Vec<CharacterClass>)> {
+        let members: Vec<Option<Vec<char>>> = self
+            .required
+            .iter()
+            .map(|classes| group_members(classes))
+            .collect();
+
+        let mut subsumed = Vec::new();
+        for (i, group) in members.iter().enumerate() {
+            let subsumed_by = members.iter().enumerate().position(|(j, other)| {
+                // An empty group can't be satisfied at all, so it doesn't make others redundant
+                let is_empty = other.as_ref().is_some_and(|other| other.is_empty());
+                let is_subset = group_is_subset(other, group);
+                let is_equal = is_subset && group_is_subset(group, other);
+                i != j && !is_empty && is_subset && (j < i || !is_equal)
+            });
+            if let Some(j) = subsumed_by {
+                subsumed.push((self.required[i].clone(), self.required[j].clone()));
+            }
+        }
+        subsumed
+    }
+
+    fn pointless_required(&self) -> Vec<Vec<CharacterClass>> {
+        let permitted: Vec<CharacterClass> = self
+            .allowed
+            .iter()
+            .chain(self.required_classes())
+            .cloned()
+            .collect();
+        let permitted = group_members(&permitted);
+
+        self.required
+            .iter()
+            .filter(|classes| {
+                let members = group_members(classes);
+                // An empty group can't be satisfied at all, which is a different problem
+                let is_empty = members.as_ref().is_some_and(|members| members.is_empty());
+                !is_empty && group_is_subset(&permitted, &members)
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn unsatisfiable_reasons(&self) -> Vec<Unsatisfiable> {
+        let mut reasons = Vec::new();
+
+        if let (Some(min_length), Some(max_length)) = (self.min_length, self.max_length) {
+            if min_length > max_length {
+                reasons.push(Unsatisfiable::MinLengthExceedsMaxLength {
+                    min_length,
+                    max_length,
+                });
+            }
+        }
+
+        if let Some(max_length) = self.max_length {
+            let required = disjoint_required(&self.required);
+            if required as u64 > max_length.into() {
+                reasons.push(Unsatisfiable::TooManyRequired {
+                    required,
+                    max_length,
+                });
+            }
+        }
+
+        for classes in self.required.iter() {
+            if group_members(classes).is_some_and(|members| members.is_empty()) {
+                reasons.push(Unsatisfiable::EmptyRequired(classes.clone()));
+            }
+        }
+
+        // A password has to be at least one character long to contain a required character
+        let min_length = self
+            .min_length
+            .unwrap_or(0)
+            .max(u32::from(!self.required.is_empty()));
+        if let Some(max_consecutive) = self.max_consecutive {
+            let mut permitted: Vec<CharacterClass> = self
+                .allowed
+                .iter()
+                .chain(self.required_classes())
+                .cloned()
+                .collect();
+            if permitted.is_empty() {
+                permitted.push(CharacterClass::AsciiPrintable);
+            }
+
+            if max_consecutive == 0 && min_length > 0 {
+                reasons.push(Unsatisfiable::MaxConsecutiveZero);
+            } else if let Some([character]) = group_members(&permitted).as_deref() {
+                if min_length > max_consecutive {
+                    reasons.push(Unsatisfiable::OnlyCharacterRepeated {
+                        character: *character,
+                        min_length,
+                        max_consecutive,
+                    });
+                }
+            }
+        }
+
+        reasons
+    }
+
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings: Vec<Finding> = self
+            .unsatisfiable_reasons()
+            .iter()
+            .map(|reason| Finding {
+                lint: "unsatisfiable",
+                severity: Severity::Error,
+                message: format!("{}, so no password can satisfy this rule", reason),
+            })
+            .collect();
+
+        let redundant = self.redundant_allowed();
+        if !redundant.is_empty() {
+            findings.push(Finding {
+                lint: "redundant-allowed",
+                severity: Severity::Warning,
+                message: format!(
+                    "`{}` is already required, so it doesn't need to be allowed",
+                    syntax::format_classes(&redundant)
+                ),
+            });
+        }
+
+        for (group, subsumed_by) in self.subsumed_required() {
+            let message = if group == subsumed_by {
+                format!(
+                    "`required: {}` appears more than once",
+                    syntax::format_classes(&group)
+                )
+            } else {
+                format!(
+                    "`required: {}` is redundant, since every character satisfying \
+                    `required: {}` satisfies it too",
+                    syntax::format_classes(&group),
+                    syntax::format_classes(&subsumed_by)
+                )
+            };
+            findings.push(Finding {
+                lint: "subsumed-required",
+                severity: Severity::Warning,
+                message,
+            });
+        }
+
+        for group in self.pointless_required() {
+            findings.push(Finding {
+                lint: "pointless-required",
+                severity: Severity::Warning,
+                message: format!(
+                    "`required: {}` adds nothing, since every permitted character satisfies it",
+                    syntax::format_classes(&group)
+                ),
+            });
+        }
+
+        if self.max_length.is_none() {
+            findings.push(Finding {
+                lint: "missing-maxlength",
+                severity: Severity::Warning,
+                message: "there's no `maxlength`, so passwords of any length are accepted"
+                    .to_string(),
+            });
+        }
+
+        if self.max_consecutive.is_none() {
+            findings.push(Finding {
+                lint: "missing-max-consecutive",
+                severity: Severity::Info,
+                message:
+                    "there's no `max-consecutive`, so passwords like `aaaaaaaa` are accepted; \
+                    consider adding one"
+                        .to_string(),
+            });
+        }
+
+        findings
+    }
+}
+
+/// Find the properties in a rules string that the parser doesn't understand; see
+/// [`syntax::unknown_properties`].
+///
+/// This is the `unknown-property` lint (warning). It works on the rules string rather than
+/// `PasswordRules`, since rules with unknown properties don't parse.
+pub fn unknown_property_findings(rules: &str) -> Vec<Finding> {
+    syntax::unknown_properties(rules)
+        .into_iter()
+        .map(|name| Finding {
+            lint: "unknown-property",
+            severity: Severity::Warning,
+            message: unknown_property_message(&name),
+        })
+        .collect()
+}
+
+/// Explain that the property `name` isn't understood
+pub fn unknown_property_message(name: &str) -> String {
+    format!(
+        "`{}` isn't a property the parser understands; it may be misspelled or new to the rules language",
+        name
+    )
+}
+
+/// Find empty clauses in a rules string, such as those left by a doubled semicolon.
+///
+/// This is the `empty-clause` lint (warning). The parser rejects empty clauses, though they're
+/// tolerated by [`crate::parse_password_rules_lenient`].
+pub fn empty_clause_findings(rules: &str) -> Vec<Finding> {
+    if syntax::blank_empty_clauses(rules) == rules {
+        return Vec::new();
+    }
+
+    vec![Finding {
+        lint: "empty-clause",
+        severity: Severity::Warning,
+        message: "the rule contains an empty clause, such as from a doubled or leading semicolon"
+            .to_string(),
+    }]
+}
+
+/// Find whitespace in a rules string that isn't canonical; see [`syntax::canonical_whitespace`].
+///
+/// This is the `non-canonical-whitespace` lint (info). The parser accepts the whitespace either
+/// way, so this is only a matter of consistency; run `lint --fail-on info` to reject it.
+pub fn whitespace_findings(rules: &str) -> Vec<Finding> {
+    let canonical = syntax::canonical_whitespace(rules);
+    if canonical == rules {
+        return Vec::new();
+    }
+
+    vec![Finding {
+        lint: "non-canonical-whitespace",
+        severity: Severity::Info,
+        message: format!(
+            "the whitespace isn't canonical; it would be `{}`",
+            canonical
+        ),
+    }]
+}
+
+/// Find custom classes that contain a character more than once; see
+/// [`syntax::duplicate_class_chars`].
+///
+/// This is the `duplicate-class-character` lint (info). The parser ignores the repeats, so they
+/// don't change what the rules mean.
+pub fn duplicate_class_char_findings(rules: &str) -> Vec<Finding> {
+    syntax::duplicate_class_chars(rules)
+        .into_iter()
+        .map(|(class, duplicates)| Finding {
+            lint: "duplicate-class-character",
+            severity: Severity::Info,
+            message: format!(
+                "the custom class `{}` contains {} more than once",
+                class,
+                duplicates
+                    .iter()
+                    .map(|c| format!("`{}`", c))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        })
+        .collect()
+}
+
+/// Why `site` doesn't look like a hostname, if it doesn't
+fn site_key_problem(site: &str) -> Option<String> {
+    if site.trim() != site {
+        return Some("it has leading or trailing whitespace".to_string());
+    }
+    if site.contains("://") {
+        return Some("it includes a URL scheme".to_string());
+    }
+    if site.contains('/') {
+        return Some("it includes a path".to_string());
+    }
+    if site.len() > 253 {
+        return Some("it's longer than 253 characters".to_string());
+    }
+
+    let labels: Vec<&str> = site.split('.').collect();
+    if labels.len() < 2 {
+        return Some("it doesn't have a top-level domain".to_string());
+    }
+    for label in labels {
+        if label.is_empty() || label.len() > 63 {
+            return Some(format!(
+                "the label `{}` isn't between 1 and 63 characters long",
+                label
+            ));
+        }
+        if let Some(c) = label
+            .chars()
+            .find(|&c| !c.is_ascii_alphanumeric() && c != '-')
+        {
+            return Some(format!("the label `{}` contains {:?}", label, c));
+        }
+        if label.starts_with('-') || label.ends_with('-') {
+            return Some(format!(
+                "the label `{}` starts or ends with a hyphen",
+                label
+            ));
+        }
+    }
+
+    None
+}
+
+/// Check that a site key from Apple's quirks file looks like a hostname, with no scheme, path, or
+/// characters that aren't valid in a domain name.
+///
+/// This is the `invalid-site-key` lint (warning).
+pub fn site_key_findings(site: &str) -> Vec<Finding> {
+    site_key_problem(site)
+        .map(|problem| Finding {
+            lint: "invalid-site-key",
+            severity: Severity::Warning,
+            message: format!(
+                "the site key {:?} doesn't look like a hostname: {}",
+                site, problem
+            ),
+        })
+        .into_iter()
+        .collect()
+}
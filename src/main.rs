@@ -1,179 +1,1791 @@
 use anyhow::{anyhow, Context};
+use colored::Colorize;
+use indicatif::{ProgressBar, ProgressStyle};
+use password_rules_checker::diff::{self, DiffOptions, SiteDiff};
+use password_rules_checker::lint::{self, LintConfig, LintLevel};
+use password_rules_checker::onepassword::{OnePasswordRecipe, ToOnePassword};
+use password_rules_checker::quirks::{self, Quirk, QuirkEntries};
+use password_rules_checker::serde_rules::SerdeRules;
+use password_rules_checker::stats::Stats;
+use password_rules_checker::syntax;
+use password_rules_checker::{
+    parse_password_rules_lenient, parse_password_rules_recover, Describe, ErrorKind, ErrorSpan,
+    Finding, Generate, GenerateError, GenerateMode, GenerateOptions, Lint, PasswordRulesErrorExt,
+    SemanticKey, Severity, Span, ToRulesString, Validate,
+};
 use password_rules_parser::error::PasswordRulesError;
-use password_rules_parser::{parse_password_rules, CharacterClass, PasswordRules};
-use serde::Deserialize;
-use std::collections::HashMap;
+use password_rules_parser::PasswordRules;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::env;
+use std::fmt;
 use std::fs;
+use std::io::{self, BufWriter, IsTerminal, Write};
+use std::ops::AddAssign;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Mutex;
 use structopt::StructOpt;
 
+/// The file passed to `--output`, if there was one. Reports are written to stdout otherwise.
+static OUTPUT_FILE: Mutex<Option<BufWriter<fs::File>>> = Mutex::new(None);
+
+/// The first error writing the report, which `main` returns once the command has finished
+static OUTPUT_ERROR: Mutex<Option<anyhow::Error>> = Mutex::new(None);
+
+/// The progress bar for the sites being processed, if one is being shown
+static PROGRESS: Mutex<Option<ProgressBar>> = Mutex::new(None);
+
+/// Show a progress bar for processing `sites` sites on stderr, unless `--quiet` was passed or
+/// stderr isn't a terminal. Finish it with [`finish_progress`].
+fn start_progress(sites: usize, common: &CommonOpts) -> ProgressBar {
+    if common.quiet || !io::stderr().is_terminal() {
+        return ProgressBar::hidden();
+    }
+
+    let progress = ProgressBar::new(sites as u64).with_style(
+        ProgressStyle::with_template("{bar:40} {pos}/{len} sites ({elapsed})")
+            .expect("the progress bar template is valid"),
+    );
+    *PROGRESS.lock().unwrap() = Some(progress.clone());
+    progress
+}
+
+/// Remove the progress bar shown by [`start_progress`], if there is one
+fn finish_progress() {
+    if let Some(progress) = PROGRESS.lock().unwrap().take() {
+        progress.finish_and_clear();
+    }
+}
+
+/// Run `f`, which prints something, with the progress bar hidden so the two don't get mixed up
+fn suspend_progress<R>(f: impl FnOnce() -> R) -> R {
+    let progress = PROGRESS.lock().unwrap().clone();
+    match progress {
+        Some(progress) => progress.suspend(f),
+        None => f(),
+    }
+}
+
+/// Write part of the report to the file passed to `--output`, or to stdout.
+///
+/// Only the first error is kept (see [`OUTPUT_ERROR`]), so a full disk or a closed pipe doesn't
+/// stop the command partway through.
+fn write_output(args: fmt::Arguments<'_>) {
+    let result = match OUTPUT_FILE.lock().unwrap().as_mut() {
+        Some(file) => file
+            .write_fmt(args)
+            .context("Failed to write the output file"),
+        None => {
+            suspend_progress(|| io::stdout().write_fmt(args)).context("Failed to write to stdout")
+        }
+    };
+    if let Err(e) = result {
+        OUTPUT_ERROR.lock().unwrap().get_or_insert(e);
+    }
+}
+
+/// Whether `--output` was passed, in which case status messages go to stderr
+fn writing_to_file() -> bool {
+    OUTPUT_FILE.lock().unwrap().is_some()
+}
+
+/// Like `print!`, but writes to the report's destination
+macro_rules! out {
+    ($($arg:tt)*) => {
+        write_output(format_args!($($arg)*))
+    };
+}
+
+/// Like `println!`, but writes to the report's destination
+macro_rules! outln {
+    () => {
+        write_output(format_args!("\n"))
+    };
+    ($($arg:tt)*) => {
+        write_output(format_args!("{}\n", format_args!($($arg)*)))
+    };
+}
+
 #[derive(Debug, StructOpt)]
-struct Opt {
-    /// The path to the password rules JSON file in the apple repo
-    file_name: PathBuf,
-    /// Path to password rules JSON file to diff against
+enum Opt {
+    /// Check that every rule in a password rules JSON file parses
+    Check {
+        /// The path to the password rules JSON file in the apple repo, or `-` to read from stdin
+        #[structopt(required_unless = "rule")]
+        file_name: Option<PathBuf>,
+        /// Check a single rules string instead of a file
+        #[structopt(long, conflicts_with_all = &["file-name", "fix", "site"])]
+        rule: Option<String>,
+        /// Rewrite `allowed` properties that can be shortened in the file
+        #[structopt(long)]
+        fix: bool,
+        #[structopt(flatten)]
+        common: CommonOpts,
+    },
+    /// Check that every rule in two password rules JSON files is semantically equivalent
+    Diff {
+        /// The path to the password rules JSON file in the apple repo, or `-` to read from stdin
+        file_name: PathBuf,
+        /// Path to password rules JSON file to diff against, or `-` to read from stdin
+        diff_against: PathBuf,
+        /// Compare `allowed` as written, instead of ignoring `allowed` classes that are also
+        /// required (which `check` suggests shortening away)
+        #[structopt(long)]
+        strict_allowed: bool,
+        /// Treat the order of the `allowed` classes as significant. Apple treats `allowed` as a
+        /// set, so by default classes written in a different order are equivalent.
+        #[structopt(long)]
+        ordered_allowed: bool,
+        #[structopt(flatten)]
+        common: CommonOpts,
+    },
+    /// Print statistics about the rules in a password rules JSON file, such as how often each
+    /// length and character class is used
+    Stats {
+        /// The path to the password rules JSON file in the apple repo, or `-` to read from stdin
+        file_name: PathBuf,
+        #[structopt(flatten)]
+        common: CommonOpts,
+    },
+    /// Group the sites in a password rules JSON file whose rules are semantically equivalent
+    Group {
+        /// The path to the password rules JSON file in the apple repo, or `-` to read from stdin
+        file_name: PathBuf,
+        #[structopt(flatten)]
+        common: CommonOpts,
+    },
+    /// Describe the rules in a password rules JSON file in plain English
+    Explain {
+        /// The path to the password rules JSON file in the apple repo, or `-` to read from stdin
+        file_name: PathBuf,
+        #[structopt(flatten)]
+        common: CommonOpts,
+    },
+    /// Check whether a password satisfies the rules for a site in a password rules JSON file
+    Validate {
+        /// The path to the password rules JSON file in the apple repo, or `-` to read from stdin
+        file_name: PathBuf,
+        /// The password to check (pass `--site` to choose which site's rules to check it against)
+        #[structopt(long)]
+        password: String,
+        #[structopt(flatten)]
+        common: CommonOpts,
+    },
+    /// Run every lint over the rules in a password rules JSON file
+    Lint {
+        /// The path to the password rules JSON file in the apple repo, or `-` to read from stdin
+        file_name: PathBuf,
+        /// Exit with an error if there are findings of this severity or worse (`info`, `warning`,
+        /// or `error`). Defaults to the lint config's `fail-on`, or `error`.
+        #[structopt(long)]
+        fail_on: Option<Severity>,
+        /// Don't report findings from this lint, such as `missing-maxlength`; can be passed more
+        /// than once, and takes precedence over the lint config
+        #[structopt(long = "allow", number_of_values = 1)]
+        allowed_lints: Vec<String>,
+        /// A TOML file that enables, disables, and sets the severities of individual lints
+        #[structopt(long)]
+        lint_config: Option<PathBuf>,
+        #[structopt(flatten)]
+        common: CommonOpts,
+    },
+    /// Generate example passwords that satisfy every rule in a password rules JSON file
+    Generate {
+        /// The path to the password rules JSON file in the apple repo, or `-` to read from stdin
+        file_name: PathBuf,
+        /// How many passwords to generate for each site
+        #[structopt(long, short = "n", default_value = "1")]
+        count: usize,
+        /// How long to make the passwords: `random` (any length the rules allow), `strongest`
+        /// (the longest length the rules allow), or `minimal` (the shortest)
+        #[structopt(long, default_value = "random")]
+        mode: GenerateMode,
+        #[structopt(flatten)]
+        common: CommonOpts,
+    },
+    /// Export the rules in a password rules JSON file as JSON approximating 1Password's password
+    /// recipes
+    Export {
+        /// The path to the password rules JSON file in the apple repo, or `-` to read from stdin
+        file_name: PathBuf,
+        #[structopt(flatten)]
+        common: CommonOpts,
+    },
+}
+
+/// Options shared by every subcommand
+#[derive(Debug, StructOpt)]
+struct CommonOpts {
+    /// The output format (`text`, `json`, `yaml`, or `csv`)
+    ///
+    /// `csv` is only supported when checking or diffing a single file.
+    #[structopt(long, default_value = "text")]
+    format: Format,
+    /// Only process the rule for the site with this key
+    #[structopt(long)]
+    site: Option<String>,
+    /// Only print errors and suggestions
+    #[structopt(long, short)]
+    quiet: bool,
+    /// Whether to parse rules in strict mode (`true` or `false`)
+    ///
+    /// This is passed to the parser as its `supply_default` flag: when set, `ascii-printable` is
+    /// added to the allowed classes of rules that neither allow nor require any.
+    #[structopt(long, default_value = "true", parse(try_from_str))]
+    strict: bool,
+    /// When to color the output (`auto`, `always`, or `never`)
+    #[structopt(long, default_value = "auto")]
+    color: ColorChoice,
+    /// Write the report to this file instead of stdout. Status messages are printed to stderr.
+    #[structopt(long, short)]
+    output: Option<PathBuf>,
+    /// Print parse errors as JSON objects, one per line, instead of as annotated text.
+    ///
+    /// Each object has the site, the kind of error, the parser's message, and the span of the
+    /// offending token. Errors go wherever they would have been printed otherwise: with the
+    /// report for text output, and to stderr for other formats.
     #[structopt(long)]
-    diff_against: Option<PathBuf>,
+    json_errors: bool,
+}
+
+impl CommonOpts {
+    /// Print a status message that isn't actionable, unless `--quiet` was passed
+    fn print_chatter(&self, message: &str) {
+        if !self.quiet {
+            print_status(self.format, message);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Json,
+    Yaml,
+    Csv,
+}
+
+impl Format {
+    /// Print `value` in this format, unless this is the text format, which has no serialized
+    /// representation
+    fn print_serialized<T: Serialize>(self, value: &T) -> Result<(), anyhow::Error> {
+        match self {
+            Format::Text => {}
+            Format::Json => outln!("{}", serde_json::to_string_pretty(value)?),
+            Format::Yaml => out!("{}", serde_yaml::to_string(value)?),
+            // `main` only allows CSV for reports that have a tabular form
+            Format::Csv => unreachable!("CSV can't represent arbitrary reports"),
+        }
+        Ok(())
+    }
+
+    /// Print the results of checking each site in this format
+    fn print_site_reports(self, site_reports: &[SiteReport]) -> Result<(), anyhow::Error> {
+        if self != Format::Csv {
+            return self.print_serialized(&site_reports);
+        }
+
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        for report in site_reports.iter() {
+            let rules = report.rules.as_ref().map(|rules| &rules.0);
+            writer.serialize(CsvRow {
+                site: &report.site,
+                min_length: rules.and_then(|rules| rules.min_length),
+                max_length: rules.and_then(|rules| rules.max_length),
+                max_consecutive: rules.and_then(|rules| rules.max_consecutive),
+                required_groups: rules.map(|rules| rules.required.len()),
+                allowed: rules.map(|rules| syntax::format_classes(&rules.allowed)),
+            })?;
+        }
+        out!("{}", String::from_utf8(writer.into_inner()?)?);
+        Ok(())
+    }
 }
 
-#[derive(Debug, Deserialize)]
-struct Quirk {
-    #[serde(rename = "password-rules")]
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "yaml" => Ok(Format::Yaml),
+            "csv" => Ok(Format::Csv),
+            _ => Err(anyhow!(
+                "Unknown format `{}`; expected `text`, `json`, `yaml`, or `csv`",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether output should be colored.
+    ///
+    /// `auto` colors output when it's written to a terminal and the `NO_COLOR` environment
+    /// variable isn't set.
+    fn enabled(self, to_terminal: bool) -> bool {
+        match self {
+            ColorChoice::Auto => env::var_os("NO_COLOR").is_none() && to_terminal,
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+impl FromStr for ColorChoice {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(anyhow!(
+                "Unknown color choice `{}`; expected `auto`, `always`, or `never`",
+                s
+            )),
+        }
+    }
+}
+
+/// The result of checking a single site, as emitted by `--format json`
+#[derive(Debug, Serialize)]
+struct SiteReport {
+    site: String,
+    /// The rules string as written in the file
     password_rules: String,
+    parsed: bool,
+    /// The parsed rules, if they parsed
+    rules: Option<SerdeRules>,
+    /// The shortened `allowed` classes in the rules syntax, if they could be shortened
+    shortened_allowed: Option<String>,
+    /// Problems with the rules, such as rules that parsed but can't be satisfied as written or
+    /// properties that the parser doesn't understand
+    warnings: Vec<String>,
+    error: Option<String>,
+}
+
+/// A row of the `--format csv` output. Fields are left blank for rules that failed to parse.
+#[derive(Debug, Serialize)]
+struct CsvRow<'a> {
+    site: &'a str,
+    min_length: Option<u32>,
+    max_length: Option<u32>,
+    max_consecutive: Option<u32>,
+    /// The number of `required` groups
+    required_groups: Option<usize>,
+    allowed: Option<String>,
+}
+
+/// The passwords generated for a single site, as emitted by `--format json`
+#[derive(Debug, Serialize)]
+struct GeneratedPasswords {
+    site: String,
+    passwords: Vec<String>,
+    /// Why passwords couldn't be generated, if they couldn't
+    error: Option<String>,
+}
+
+/// The result of validating a password, as emitted by `--format json`
+#[derive(Debug, Serialize)]
+struct PasswordReport {
+    site: String,
+    valid: bool,
+    violations: Vec<String>,
+}
+
+/// A rule that failed to parse, as emitted by `--json-errors`
+#[derive(Debug, Serialize)]
+struct ParseErrorReport<'a> {
+    site: &'a str,
+    kind: ErrorKind,
+    message: String,
+    /// Where in the rules string parsing failed, if the error has a location
+    span: Option<Span>,
+}
+
+impl<'a> ParseErrorReport<'a> {
+    fn new(site: &'a str, parsed_from: &str, e: &PasswordRulesError) -> Self {
+        let mut e = e.clone();
+        e.locate_in(parsed_from);
+        ParseErrorReport {
+            site,
+            kind: e.kind(),
+            message: e.to_string(),
+            span: e.span(parsed_from),
+        }
+    }
+
+    fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("parse error reports always serialize")
+    }
+}
+
+/// A plain English description of a site's rules, as emitted by `--format json`
+#[derive(Debug, Serialize)]
+struct Explanation {
+    site: String,
+    explanation: String,
+}
+
+/// A lint finding for a single site, as emitted by `--format json`
+#[derive(Debug, Serialize)]
+struct SiteFinding {
+    site: String,
+    #[serde(flatten)]
+    finding: Finding,
+}
+
+/// Sites whose rules are semantically equivalent, as emitted by `--format json`
+#[derive(Debug, Serialize)]
+struct RulesGroup {
+    /// The rules shared by every site in the group, in the rules syntax
+    policy: String,
+    rules: SerdeRules,
+    sites: Vec<String>,
+}
+
+/// Parse `rules` the way every subcommand does: with the escapes and ranges in its custom classes
+/// expanded, tolerating empty clauses, and with the error's columns counted in characters of
+/// `rules`
+fn parse_rules(rules: &str, strict: bool) -> Result<PasswordRules, PasswordRulesError> {
+    let expanded = syntax::expand_classes(rules).map_err(|mut e| {
+        e.locate_in(rules);
+        e
+    })?;
+    parse_password_rules_lenient(&expanded, strict).map_err(|mut e| {
+        syntax::locate_expanded_error(&mut e, rules, &expanded);
+        e.locate_in(rules);
+        e
+    })
+}
+
+/// Every error in `rules`, parsed like `parse_rules` does but recovering after each one (see
+/// `parse_password_rules_recover`)
+fn recover_errors(rules: &str, strict: bool) -> Vec<PasswordRulesError> {
+    let expanded = match syntax::expand_classes(rules) {
+        Ok(expanded) => expanded,
+        Err(e) => return vec![e],
+    };
+
+    let (_, mut errors) = parse_password_rules_recover(&expanded, strict);
+    for e in errors.iter_mut() {
+        syntax::locate_expanded_error(e, rules, &expanded);
+    }
+    errors
+}
+
+/// The results of parsing rules strings, so that a string shared by several sites is only parsed
+/// once.
+///
+/// Results are keyed by the exact rules string, and a cache is only ever filled using a single
+/// `strict` setting.
+struct ParseCache<'a> {
+    strict: bool,
+    results: HashMap<&'a str, Result<PasswordRules, PasswordRulesError>>,
+}
+
+impl<'a> ParseCache<'a> {
+    /// Parse every distinct rules string in `quirks`, in parallel
+    fn new(quirks: impl IntoIterator<Item = &'a Quirk>, strict: bool) -> Self {
+        let distinct: HashSet<&str> = quirks
+            .into_iter()
+            .map(|quirk| quirk.password_rules.as_str())
+            .collect();
+        let results = distinct
+            .into_par_iter()
+            .map(|rules| (rules, parse_rules(rules, strict)))
+            .collect();
+
+        Self { strict, results }
+    }
+
+    /// The result of parsing `rules`, which is parsed now if it wasn't parsed ahead of time
+    fn parse(&self, rules: &str) -> Result<PasswordRules, PasswordRulesError> {
+        match self.results.get(rules) {
+            Some(result) => result.clone(),
+            None => parse_rules(rules, self.strict),
+        }
+    }
+}
+
+/// Whether `path` is `-`, meaning stdin should be read instead of a file
+fn is_stdin(path: &Path) -> bool {
+    path == Path::new("-")
+}
+
+/// Load the entries of the quirks file at `path` (or stdin, if `path` is `-`), in the order
+/// they're written
+fn load_quirk_entries(path: &Path) -> Result<QuirkEntries, anyhow::Error> {
+    if !is_stdin(path) {
+        return Ok(quirks::load_quirk_entries(path)?);
+    }
+
+    quirks::read_quirk_entries(io::stdin().lock()).context("Failed to parse JSON loaded from stdin")
 }
 
-fn load_rules_map(p: impl AsRef<Path>) -> Result<HashMap<String, Quirk>, anyhow::Error> {
-    let path = p.as_ref();
+/// Load the quirks in the file at `p`, keyed (and therefore ordered) by site
+fn load_rules_map(p: impl AsRef<Path>) -> Result<BTreeMap<String, Quirk>, anyhow::Error> {
+    Ok(load_quirk_entries(p.as_ref())?.into_map())
+}
+
+/// Remove every entry from `quirks` other than the one for `site`.
+///
+/// Errors if `quirks` doesn't contain an entry for `site`.
+fn retain_site(
+    quirks: &mut BTreeMap<String, Quirk>,
+    site: &str,
+    loaded_from: &Path,
+) -> Result<(), anyhow::Error> {
+    let quirk = quirks.remove(site).ok_or_else(|| {
+        anyhow!(
+            "The quirks loaded from {} didn't contain an entry for {}",
+            loaded_from.to_string_lossy(),
+            site
+        )
+    })?;
+
+    quirks.clear();
+    quirks.insert(site.to_string(), quirk);
+    Ok(())
+}
+
+/// Rewrite every rule in the file at `path` whose `allowed` property can be shortened, returning
+/// the number of rules that were rewritten.
+///
+/// Rules that fail to parse are left untouched, as is everything in the file other than the
+/// `password-rules` strings that change.
+fn fix_rules_file(path: &Path, common: &CommonOpts) -> Result<usize, anyhow::Error> {
     let json_string = fs::read_to_string(path)
         .with_context(|| format!("Failed to read file at {}", path.to_string_lossy()))?;
+    let mut quirks: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&json_string)
+        .with_context(|| {
+            format!(
+                "Failed to parse JSON loaded from {}",
+                path.to_string_lossy()
+            )
+        })?;
 
-    Ok(serde_json::from_str(&json_string).with_context(|| {
-        format!(
-            "Failed to parse JSON loaded from {}",
-            path.to_string_lossy()
-        )
-    })?)
+    let mut fixed = 0;
+    for (site, quirk) in quirks.iter_mut() {
+        if common.site.as_ref().is_some_and(|s| s != site) {
+            continue;
+        }
+
+        let password_rules = match quirk.get_mut("password-rules") {
+            Some(serde_json::Value::String(password_rules)) => password_rules,
+            _ => continue,
+        };
+        let parsed = match parse_rules(password_rules, common.strict) {
+            Ok(parsed) => parsed,
+            Err(_) => continue,
+        };
+
+        if let Some(shortened_allowed) = parsed.shortened_allowed() {
+            *password_rules = syntax::replace_allowed(password_rules, &shortened_allowed);
+            fixed += 1;
+        }
+    }
+
+    // Match the indentation used by the apple repo
+    let mut output = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+    let mut serializer = serde_json::Serializer::with_formatter(&mut output, formatter);
+    quirks.serialize(&mut serializer)?;
+    output.push(b'\n');
+
+    fs::write(path, output)
+        .with_context(|| format!("Failed to write file at {}", path.to_string_lossy()))?;
+
+    Ok(fixed)
 }
 
-fn print_password_rules_error(site: &str, parsed_from: &str, e: PasswordRulesError) {
-    println!("{}:\n", site);
-    println!("{}\n", e.to_string_pretty(parsed_from).unwrap());
+fn print_password_rules_errors(
+    site: &str,
+    parsed_from: &str,
+    errors: &[PasswordRulesError],
+    json_errors: bool,
+) {
+    if json_errors {
+        for e in errors.iter() {
+            outln!("{}", ParseErrorReport::new(site, parsed_from, e).to_json());
+        }
+        return;
+    }
+
+    outln!("{}\n", format!("{}:", site).red());
+    for e in errors.iter() {
+        // Point the caret at the right character even if there's non-ASCII text before it
+        let mut e = e.clone();
+        e.locate_in(parsed_from);
+        // Logs and redirected output shouldn't have color codes in them
+        let pretty = if colored::control::SHOULD_COLORIZE.should_colorize() {
+            e.to_string_pretty(parsed_from)
+        } else {
+            e.to_string_plain(parsed_from)
+        };
+        match pretty {
+            Ok(pretty) => outln!("{}\n", pretty),
+            Err(_) => outln!("{}\n", e),
+        }
+    }
 }
 
-fn remove_unecessary_allows(rules: &PasswordRules) -> Vec<CharacterClass> {
-    rules
-        .allowed
-        .iter()
-        .filter(|allowed_class| {
-            // Keep only rules that aren't present in the required classes
-            !rules
-                .required
+/// Report a parse error in a way that's appropriate for the output format
+fn report_parse_error(site: &str, parsed_from: &str, e: PasswordRulesError, common: &CommonOpts) {
+    match common.format {
+        Format::Text => print_password_rules_errors(site, parsed_from, &[e], common.json_errors),
+        Format::Json | Format::Yaml | Format::Csv if common.json_errors => {
+            let report = ParseErrorReport::new(site, parsed_from, &e);
+            suspend_progress(|| eprintln!("{}", report.to_json()))
+        }
+        Format::Json | Format::Yaml | Format::Csv => {
+            suspend_progress(|| eprintln!("{}: {}", site, e))
+        }
+    }
+}
+
+/// Print a human-oriented status message.
+///
+/// This goes to stdout for text output and to stderr for JSON output (or when the report is
+/// written to a file) so it can't corrupt the report.
+fn print_status(format: Format, message: &str) {
+    match format {
+        Format::Text if !writing_to_file() => suspend_progress(|| println!("{}", message)),
+        _ => suspend_progress(|| eprintln!("{}", message)),
+    }
+}
+
+/// Counts of the outcomes of checking a set of rules
+#[derive(Debug, Default, Clone)]
+struct CheckCounts {
+    sites: usize,
+    /// Every distinct rules string that was checked
+    distinct_rules: HashSet<String>,
+    failed_to_parse: usize,
+    can_be_shortened: usize,
+    with_warnings: usize,
+}
+
+impl CheckCounts {
+    fn from_reports(site_reports: &[SiteReport]) -> Self {
+        Self {
+            sites: site_reports.len(),
+            distinct_rules: site_reports
                 .iter()
-                .flatten()
-                .any(|required_class| *allowed_class == required_class)
-        })
-        .cloned()
-        .collect()
+                .map(|r| r.password_rules.clone())
+                .collect(),
+            failed_to_parse: site_reports.iter().filter(|r| !r.parsed).count(),
+            can_be_shortened: site_reports
+                .iter()
+                .filter(|r| r.shortened_allowed.is_some())
+                .count(),
+            with_warnings: site_reports
+                .iter()
+                .filter(|r| !r.warnings.is_empty())
+                .count(),
+        }
+    }
+
+    fn print(&self, common: &CommonOpts) {
+        if self.failed_to_parse == 0 {
+            common.print_chatter(
+                &"All password rules parsed successfully!"
+                    .green()
+                    .to_string(),
+            );
+        }
+
+        common.print_chatter(
+            &format!(
+                "Checked {} sites ({} distinct rules): {} parsed, {} failed to parse, {} have warnings, {} can have their `allowed` property shortened",
+                self.sites,
+                self.distinct_rules.len(),
+                self.sites - self.failed_to_parse,
+                self.failed_to_parse,
+                self.with_warnings,
+                self.can_be_shortened
+            ),
+        );
+    }
 }
 
-fn main() -> Result<(), anyhow::Error> {
-    let opt = Opt::from_args();
+impl AddAssign for CheckCounts {
+    fn add_assign(&mut self, other: Self) {
+        self.sites += other.sites;
+        self.distinct_rules.extend(other.distinct_rules);
+        self.failed_to_parse += other.failed_to_parse;
+        self.can_be_shortened += other.can_be_shortened;
+        self.with_warnings += other.with_warnings;
+    }
+}
 
-    let quirks_parsed = load_rules_map(opt.file_name)?;
-    let quirks_to_diff_parsed = if let Some(p) = opt.diff_against.as_ref() {
-        Some(load_rules_map(p)?)
-    } else {
-        None
-    };
+/// Find problems with rules that parsed successfully but can't be satisfied as written
+fn rule_warnings(rules: &PasswordRules) -> Vec<String> {
+    rules
+        .unsatisfiable_reasons()
+        .iter()
+        .map(|reason| format!("{}, so no password can satisfy this rule", reason))
+        .collect()
+}
 
-    let mut failed_to_parse = 0;
+/// Parse every rule in `quirks_parsed` using `cache`, printing errors, warnings, and shortening
+/// suggestions when the output format is text.
+fn check_quirks(
+    quirks_parsed: &BTreeMap<String, Quirk>,
+    cache: &ParseCache<'_>,
+    common: &CommonOpts,
+) -> Vec<SiteReport> {
+    let mut site_reports = Vec::new();
+    let progress = start_progress(quirks_parsed.len(), common);
     for (site, quirk) in quirks_parsed.iter() {
-        match parse_password_rules(&quirk.password_rules, true) {
+        progress.inc(1);
+        match cache.parse(&quirk.password_rules) {
             Ok(quirk_parsed) => {
-                let possibly_shortened_allows = remove_unecessary_allows(&quirk_parsed);
+                let shortened_allowed = quirk_parsed
+                    .shortened_allowed()
+                    .map(|shortened_allowed| syntax::format_classes(&shortened_allowed));
 
-                if quirk_parsed.allowed != possibly_shortened_allows {
-                    // TODO: pretty print the suggestion
-                    println!(
-                        "{}: the `allowed` property for this rule can be shortened to: {:?}",
-                        site, possibly_shortened_allows
+                match (common.format, shortened_allowed.as_deref()) {
+                    (Format::Text, Some("")) => outln!(
+                        "{}",
+                        format!("{}: the `allowed` property for this rule can be removed", site)
+                            .yellow()
+                    ),
+                    (Format::Text, Some(shortened_allowed)) => outln!(
+                        "{}",
+                        format!(
+                            "{}: the `allowed` property for this rule can be shortened to: `allowed: {};`",
+                            site, shortened_allowed
+                        )
+                        .yellow()
+                    ),
+                    _ => {}
+                }
+
+                let warnings = rule_warnings(&quirk_parsed);
+                if common.format == Format::Text {
+                    for warning in warnings.iter() {
+                        outln!("{}", format!("{}: warning: {}", site, warning).yellow());
+                    }
+                }
+
+                site_reports.push(SiteReport {
+                    site: site.to_string(),
+                    password_rules: quirk.password_rules.clone(),
+                    parsed: true,
+                    rules: Some(SerdeRules(quirk_parsed)),
+                    shortened_allowed,
+                    warnings,
+                    error: None,
+                });
+            }
+            Err(e) => {
+                let warnings: Vec<String> = syntax::unknown_properties(&quirk.password_rules)
+                    .iter()
+                    .map(|name| lint::unknown_property_message(name))
+                    .collect();
+
+                if common.format == Format::Text {
+                    // Show every problem with the rule at once rather than just the first
+                    let errors = recover_errors(&quirk.password_rules, common.strict);
+                    print_password_rules_errors(
+                        site,
+                        &quirk.password_rules,
+                        &errors,
+                        common.json_errors,
                     );
+                    for warning in warnings.iter() {
+                        outln!("{}", format!("{}: warning: {}", site, warning).yellow());
+                    }
+                } else if common.json_errors {
+                    // The report itself is on stdout, so the errors go to stderr
+                    let errors = recover_errors(&quirk.password_rules, common.strict);
+                    suspend_progress(|| {
+                        for error in errors.iter() {
+                            let report = ParseErrorReport::new(site, &quirk.password_rules, error);
+                            eprintln!("{}", report.to_json());
+                        }
+                    });
                 }
+
+                site_reports.push(SiteReport {
+                    site: site.to_string(),
+                    password_rules: quirk.password_rules.clone(),
+                    parsed: false,
+                    rules: None,
+                    shortened_allowed: None,
+                    warnings,
+                    error: Some(e.to_string()),
+                });
+            }
+        }
+    }
+
+    finish_progress();
+    site_reports
+}
+
+/// Check every rule in the file at `file_name`, fixing shortenable `allowed` properties in place if
+/// `fix` is set.
+///
+/// Returns the counts of the outcomes alongside the per-site reports, which are printed here if the
+/// output format is text.
+fn check_file(
+    file_name: &Path,
+    fix: bool,
+    common: &CommonOpts,
+) -> Result<(CheckCounts, Vec<SiteReport>), anyhow::Error> {
+    let entries = load_quirk_entries(file_name)?;
+    for (site, other_site) in entries.colliding_keys() {
+        let warning = if site == other_site {
+            format!(
+                "warning: the site key `{}` appears more than once; only the last entry is used",
+                site
+            )
+        } else {
+            format!(
+                "warning: the site keys `{}` and `{}` refer to the same host",
+                site, other_site
+            )
+        };
+        match common.format {
+            Format::Text => outln!("{}", warning.yellow()),
+            Format::Json | Format::Yaml | Format::Csv => eprintln!("{}", warning),
+        }
+    }
+
+    let mut quirks_parsed = entries.into_map();
+    if let Some(site) = common.site.as_ref() {
+        retain_site(&mut quirks_parsed, site, file_name)?;
+    }
+
+    // Parsing is independent per site, so do it in parallel and report on the results afterwards
+    let cache = ParseCache::new(quirks_parsed.values(), common.strict);
+    let site_reports = check_quirks(&quirks_parsed, &cache, common);
+    let counts = CheckCounts::from_reports(&site_reports);
+
+    if fix {
+        let fixed = fix_rules_file(file_name, common)?;
+        common.print_chatter(&format!(
+            "Shortened the `allowed` property of {} rules in {}",
+            fixed,
+            file_name.to_string_lossy()
+        ));
+    }
+
+    Ok((counts, site_reports))
+}
+
+/// Recursively find every `.json` file in `dir`, sorted by path
+fn find_json_files(dir: &Path) -> Result<Vec<PathBuf>, anyhow::Error> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)
+        .with_context(|| format!("Failed to read directory at {}", dir.to_string_lossy()))?
+    {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(find_json_files(&path)?);
+        } else if path.extension().is_some_and(|ext| ext == "json") {
+            files.push(path);
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Check every JSON file in `dir`, reporting the results grouped by file.
+///
+/// A file that can't be loaded doesn't stop the others from being checked. When `--site` is set,
+/// files that don't contain an entry for the site are skipped.
+fn check_directory(dir: &Path, fix: bool, common: &CommonOpts) -> Result<(), anyhow::Error> {
+    let mut totals = CheckCounts::default();
+    let mut files_checked = 0;
+    let mut failed_to_load = 0;
+    let mut file_reports = BTreeMap::new();
+
+    for file_name in find_json_files(dir)? {
+        if let Some(site) = common.site.as_ref() {
+            match load_rules_map(&file_name) {
+                Ok(quirks) if !quirks.contains_key(site) => continue,
+                _ => {}
+            }
+        }
+
+        if common.format == Format::Text {
+            outln!("Checking {}", file_name.to_string_lossy());
+        }
+
+        match check_file(&file_name, fix, common) {
+            Ok((counts, site_reports)) => {
+                counts.print(common);
+                totals += counts;
+                file_reports.insert(file_name.to_string_lossy().into_owned(), site_reports);
             }
             Err(e) => {
-                print_password_rules_error(site, &quirk.password_rules, e);
-                failed_to_parse += 1;
+                eprintln!("Error: {:#}", e);
+                failed_to_load += 1;
             }
         }
+        files_checked += 1;
     }
 
-    if failed_to_parse == 0 {
-        println!("All password rules parsed successfully!");
-    } else {
-        return Ok(());
+    common.format.print_serialized(&file_reports)?;
+
+    common.print_chatter(&format!(
+        "Checked {} files in {}: {} couldn't be loaded",
+        files_checked,
+        dir.to_string_lossy(),
+        failed_to_load
+    ));
+    totals.print(common);
+
+    if failed_to_load > 0 || totals.failed_to_parse > 0 {
+        return Err(anyhow!(
+            "Some files failed to load or contained rules that failed to parse"
+        ));
     }
 
-    if let Some(quirks_to_diff_parsed) = quirks_to_diff_parsed {
-        println!(
-            "Diffing against the rules loaded from {}",
-            opt.diff_against.unwrap().to_string_lossy()
+    Ok(())
+}
+
+/// The differences between two quirks files, as emitted by `diff --format json`
+#[derive(Debug, Serialize)]
+struct DiffReport<'a> {
+    /// The sites that are only in the file being diffed
+    added: Vec<String>,
+    /// The sites that are only in the file being diffed against
+    removed: Vec<String>,
+    /// The sites in both files whose rules aren't semantically equivalent
+    changed: &'a [SiteDiff],
+    /// The sites in both files that couldn't be compared because their rules failed to parse
+    failed_to_parse: &'a [String],
+}
+
+/// The outcome of comparing the rules for a site that's in both of the files being diffed
+enum SiteComparison<'a> {
+    /// One of the rules failed to parse, so they couldn't be compared
+    ParseError {
+        rules: &'a str,
+        error: PasswordRulesError,
+    },
+    /// The differences between the rules, if there were any
+    Compared(Option<SiteDiff>),
+}
+
+/// Check that every rule in `quirks_parsed` is semantically equivalent to the rule for the same
+/// site in `quirks_to_diff_parsed`.
+///
+/// Sites that are only present in one of the two maps are reported, but only the sites present in
+/// both are compared. Sites whose rules fail to parse in either map are reported and skipped.
+/// `cache` should already hold the rules of both maps, so that neither is parsed again.
+fn diff_quirks(
+    quirks_parsed: &BTreeMap<String, Quirk>,
+    quirks_to_diff_parsed: &BTreeMap<String, Quirk>,
+    diff_against: &Path,
+    cache: &ParseCache<'_>,
+    options: &DiffOptions,
+    common: &CommonOpts,
+) -> Result<(), anyhow::Error> {
+    common.print_chatter(&format!(
+        "Diffing against the rules loaded from {}",
+        diff_against.to_string_lossy()
+    ));
+
+    let added: Vec<String> = quirks_parsed
+        .keys()
+        .filter(|site| !quirks_to_diff_parsed.contains_key(*site))
+        .cloned()
+        .collect();
+    let removed: Vec<String> = quirks_to_diff_parsed
+        .keys()
+        .filter(|site| !quirks_parsed.contains_key(*site))
+        .cloned()
+        .collect();
+    for site in added.iter() {
+        print_status(
+            common.format,
+            &format!("{}: missing from the rules being diffed against", site),
         );
+    }
+    for site in removed.iter() {
+        print_status(
+            common.format,
+            &format!("{}: only present in the rules being diffed against", site),
+        );
+    }
+
+    // Compare the sites in parallel, then report on them in order
+    let progress = start_progress(quirks_parsed.len(), common);
+    let comparisons: Vec<(&String, SiteComparison)> = quirks_parsed
+        .par_iter()
+        .filter_map(|(site, quirk)| {
+            progress.inc(1);
+            let other_quirk = quirks_to_diff_parsed.get(site)?;
+
+            let parse = |rules: &str| {
+                let mut parsed = cache.parse(rules)?;
+                // The parser sorts the classes, so get them back in the order they're written
+                if options.ordered_allowed && !parsed.allowed.is_empty() {
+                    parsed.allowed = syntax::written_allowed(&syntax::expand_classes(rules)?);
+                }
+                Ok(parsed)
+            };
+            let parsed = parse(&quirk.password_rules);
+            let other_parsed = parse(&other_quirk.password_rules);
+            let comparison = match (parsed, other_parsed) {
+                (Ok(parsed), Ok(other_parsed)) => {
+                    let fields = diff::diff_rules_with(&parsed, &other_parsed, options);
+                    SiteComparison::Compared(if fields.is_empty() {
+                        None
+                    } else {
+                        Some(SiteDiff {
+                            site: site.to_string(),
+                            fields,
+                        })
+                    })
+                }
+                (Err(error), _) => SiteComparison::ParseError {
+                    rules: &quirk.password_rules,
+                    error,
+                },
+                (_, Err(error)) => SiteComparison::ParseError {
+                    rules: &other_quirk.password_rules,
+                    error,
+                },
+            };
+            Some((site, comparison))
+        })
+        .collect();
+    finish_progress();
 
-        if quirks_to_diff_parsed.len() != quirks_parsed.len() {
+    let mut site_diffs = Vec::new();
+    let mut failed_to_parse = Vec::new();
+    for (site, comparison) in comparisons {
+        match comparison {
+            SiteComparison::ParseError { rules, error } => {
+                report_parse_error(site, rules, error, common);
+                failed_to_parse.push(site.clone());
+            }
+            SiteComparison::Compared(site_diff) => {
+                common.print_chatter(&format!("Checking {}", site));
+                site_diffs.extend(site_diff);
+            }
+        }
+    }
+
+    match common.format {
+        Format::Text => {
+            for site_diff in site_diffs.iter() {
+                outln!("{}", site_diff);
+            }
+        }
+        Format::Json | Format::Yaml => common.format.print_serialized(&DiffReport {
+            added,
+            removed,
+            changed: &site_diffs,
+            failed_to_parse: &failed_to_parse,
+        })?,
+        // The CSV output is the rules being diffed, so the diffs go to stderr
+        Format::Csv => {
+            for site_diff in site_diffs.iter() {
+                eprintln!("{}", site_diff);
+            }
+        }
+    }
+
+    match (site_diffs.len(), failed_to_parse.len()) {
+        (0, 0) => {}
+        (mismatched, 0) => {
             return Err(anyhow!(
-                "The number of quirks is different between the two files being compared; \
-                they must have the same number of rules"
-            ));
+                "{} rules were not semantically equivalent",
+                mismatched
+            ))
         }
+        (mismatched, failed_to_parse) => {
+            return Err(anyhow!(
+                "{} rules were not semantically equivalent and {} couldn't be compared because \
+                they failed to parse",
+                mismatched,
+                failed_to_parse
+            ))
+        }
+    }
 
-        for (site, quirk) in quirks_parsed.iter() {
-            let other_quirk = quirks_to_diff_parsed.get(site).ok_or_else(|| {
-                anyhow!(
-                    "The quirks being diffed against didn't contain an entry for {}",
-                    site
-                )
-            })?;
+    common.print_chatter(
+        &"All rules were semantically equivalent!"
+            .green()
+            .to_string(),
+    );
 
-            // We already verified that all of these rules parse correctly above
-            let mut quirk_parsed = parse_password_rules(&quirk.password_rules, true).unwrap();
-            let mut other_quirk_parsed = match parse_password_rules(
-                &other_quirk.password_rules,
-                true,
-            ) {
-                Ok(parsed) => parsed,
-                Err(e) => {
-                    print_password_rules_error(site, &other_quirk.password_rules, e);
-                    return Err(anyhow!("One of the password rules in the quirks being diffed against failed to parse"));
+    Ok(())
+}
+
+/// Print statistics gathered across every rule in `quirks_parsed`.
+///
+/// Statistics are only printed if every rule parses successfully.
+fn print_stats(
+    quirks_parsed: &BTreeMap<String, Quirk>,
+    common: &CommonOpts,
+) -> Result<(), anyhow::Error> {
+    let cache = ParseCache::new(quirks_parsed.values(), common.strict);
+    let mut stats = Stats::default();
+    let mut failed_to_parse = 0;
+    for (site, quirk) in quirks_parsed.iter() {
+        match cache.parse(&quirk.password_rules) {
+            Ok(parsed) => stats.add(&parsed),
+            Err(e) => {
+                report_parse_error(site, &quirk.password_rules, e, common);
+                failed_to_parse += 1;
+            }
+        }
+    }
+
+    if failed_to_parse > 0 {
+        return Err(anyhow!(
+            "{} password rules failed to parse; fix them to see statistics",
+            failed_to_parse
+        ));
+    }
+
+    match common.format {
+        Format::Text => out!("{}", stats),
+        _ => common.format.print_serialized(&stats)?,
+    }
+
+    Ok(())
+}
+
+/// Print the sites in `quirks_parsed` grouped by semantically equivalent rules, largest group
+/// first.
+///
+/// Groups are only printed if every rule parses successfully.
+fn group_quirks(
+    quirks_parsed: &BTreeMap<String, Quirk>,
+    common: &CommonOpts,
+) -> Result<(), anyhow::Error> {
+    let cache = ParseCache::new(quirks_parsed.values(), common.strict);
+    // Groups are kept in the order they're found, and indexed by their semantic key
+    let mut groups: Vec<(PasswordRules, Vec<String>)> = Vec::new();
+    let mut group_indices: HashMap<SemanticKey, usize> = HashMap::new();
+    let mut failed_to_parse = 0;
+    for (site, quirk) in quirks_parsed.iter() {
+        let parsed = match cache.parse(&quirk.password_rules) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                report_parse_error(site, &quirk.password_rules, e, common);
+                failed_to_parse += 1;
+                continue;
+            }
+        };
+
+        match group_indices.entry(SemanticKey::new(&parsed)) {
+            Entry::Occupied(entry) => groups[*entry.get()].1.push(site.clone()),
+            Entry::Vacant(entry) => {
+                entry.insert(groups.len());
+                groups.push((parsed, vec![site.clone()]));
+            }
+        }
+    }
+
+    if failed_to_parse > 0 {
+        return Err(anyhow!(
+            "{} password rules failed to parse; fix them to see groups",
+            failed_to_parse
+        ));
+    }
+
+    // The sort is stable, so groups of the same size stay ordered by their first site
+    groups.sort_by_key(|(_, sites)| std::cmp::Reverse(sites.len()));
+    let groups: Vec<RulesGroup> = groups
+        .into_iter()
+        .map(|(rules, sites)| RulesGroup {
+            policy: rules.to_rules_string(),
+            rules: SerdeRules(rules),
+            sites,
+        })
+        .collect();
+
+    match common.format {
+        Format::Text => {
+            for group in groups.iter() {
+                outln!("{} ({} sites)", group.policy.bold(), group.sites.len());
+                for site in group.sites.iter() {
+                    outln!("  {}", site);
+                }
+                outln!();
+            }
+        }
+        _ => common.format.print_serialized(&groups)?,
+    }
+
+    common.print_chatter(&format!(
+        "Grouped {} sites into {} distinct policies",
+        quirks_parsed.len(),
+        groups.len()
+    ));
+
+    Ok(())
+}
+
+/// Print a plain English description of every rule in `quirks_parsed`.
+///
+/// Errors if any rule fails to parse.
+fn explain_quirks(
+    quirks_parsed: &BTreeMap<String, Quirk>,
+    common: &CommonOpts,
+) -> Result<(), anyhow::Error> {
+    let cache = ParseCache::new(quirks_parsed.values(), common.strict);
+    let mut explanations = Vec::new();
+    let mut failed_to_parse = 0;
+    for (site, quirk) in quirks_parsed.iter() {
+        match cache.parse(&quirk.password_rules) {
+            Ok(parsed) => {
+                let explanation = parsed.describe();
+                if common.format == Format::Text {
+                    outln!("{}: {}", site.bold(), explanation);
+                }
+                explanations.push(Explanation {
+                    site: site.clone(),
+                    explanation,
+                });
+            }
+            Err(e) => {
+                report_parse_error(site, &quirk.password_rules, e, common);
+                failed_to_parse += 1;
+            }
+        }
+    }
+
+    common.format.print_serialized(&explanations)?;
+
+    if failed_to_parse > 0 {
+        return Err(anyhow!(
+            "{} password rules failed to parse",
+            failed_to_parse
+        ));
+    }
+
+    Ok(())
+}
+
+/// A site's rules exported for 1Password, as emitted by `export`
+#[derive(Debug, Serialize)]
+struct ExportedRecipe {
+    site: String,
+    recipe: OnePasswordRecipe,
+}
+
+/// Export the rules in `quirks_parsed` as 1Password recipes.
+///
+/// The recipes are always printed as data: as JSON unless `--format yaml` is passed. Errors if
+/// any rule fails to parse.
+fn export_quirks(
+    quirks_parsed: &BTreeMap<String, Quirk>,
+    common: &CommonOpts,
+) -> Result<(), anyhow::Error> {
+    let cache = ParseCache::new(quirks_parsed.values(), common.strict);
+    let mut recipes = Vec::new();
+    let mut failed_to_parse = 0;
+    for (site, quirk) in quirks_parsed.iter() {
+        match cache.parse(&quirk.password_rules) {
+            Ok(parsed) => {
+                let recipe = parsed.to_onepassword();
+                // The recipes are printed to stdout in every format
+                for note in recipe.notes.iter() {
+                    eprintln!("{}: {}", site, note);
                 }
+                recipes.push(ExportedRecipe {
+                    site: site.clone(),
+                    recipe,
+                });
+            }
+            Err(e) => {
+                report_parse_error(site, &quirk.password_rules, e, common);
+                failed_to_parse += 1;
+            }
+        }
+    }
+
+    match common.format {
+        Format::Yaml => Format::Yaml.print_serialized(&recipes)?,
+        _ => Format::Json.print_serialized(&recipes)?,
+    }
+
+    if failed_to_parse > 0 {
+        return Err(anyhow!(
+            "{} password rules failed to parse",
+            failed_to_parse
+        ));
+    }
+
+    Ok(())
+}
+
+/// Load the lint config file at `path`
+fn load_lint_config(path: &Path) -> Result<LintConfig, anyhow::Error> {
+    let config = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read lint config at {}", path.to_string_lossy()))?;
+    toml::from_str(&config)
+        .with_context(|| format!("Failed to parse lint config at {}", path.to_string_lossy()))
+}
+
+/// Run every lint over the rules in `quirks_parsed`, printing the findings grouped by severity.
+///
+/// Rules that fail to parse are reported as `parse-error` findings, and the other findings are
+/// adjusted by `config`. Errors if there are any findings at least as severe as `fail_on`.
+fn lint_quirks(
+    quirks_parsed: &BTreeMap<String, Quirk>,
+    fail_on: Severity,
+    config: &LintConfig,
+    common: &CommonOpts,
+) -> Result<(), anyhow::Error> {
+    let cache = ParseCache::new(quirks_parsed.values(), common.strict);
+    let site_findings: Vec<SiteFinding> = quirks_parsed
+        .par_iter()
+        .flat_map_iter(|(site, quirk)| {
+            let mut findings = match cache.parse(&quirk.password_rules) {
+                Ok(parsed) => parsed.findings(),
+                Err(e) => vec![Finding {
+                    lint: "parse-error",
+                    severity: Severity::Error,
+                    // The parser's errors can span several lines
+                    message: format!(
+                        "the rule failed to parse: {}",
+                        e.to_string()
+                            .split_whitespace()
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    ),
+                }],
             };
+            findings.extend(lint::empty_clause_findings(&quirk.password_rules));
+            findings.extend(lint::whitespace_findings(&quirk.password_rules));
+            findings.extend(lint::duplicate_class_char_findings(&quirk.password_rules));
+            findings.extend(lint::unknown_property_findings(&quirk.password_rules));
+            findings.extend(lint::site_key_findings(site));
+            config.apply(&mut findings);
 
-            quirk_parsed.allowed = remove_unecessary_allows(&quirk_parsed);
-            other_quirk_parsed.allowed = remove_unecessary_allows(&other_quirk_parsed);
+            findings.into_iter().map(move |finding| SiteFinding {
+                site: site.clone(),
+                finding,
+            })
+        })
+        .collect();
+
+    match common.format {
+        Format::Text => {
+            for severity in [Severity::Error, Severity::Warning, Severity::Info].iter() {
+                let findings: Vec<&SiteFinding> = site_findings
+                    .iter()
+                    .filter(|f| f.finding.severity == *severity)
+                    .collect();
+                if findings.is_empty() {
+                    continue;
+                }
+
+                outln!("{}:", severity);
+                for f in findings {
+                    let line = format!("  {}: {} [{}]", f.site, f.finding.message, f.finding.lint);
+                    match severity {
+                        Severity::Error => outln!("{}", line.red()),
+                        Severity::Warning => outln!("{}", line.yellow()),
+                        Severity::Info => outln!("{}", line),
+                    }
+                }
+                outln!();
+            }
+        }
+        _ => common.format.print_serialized(&site_findings)?,
+    }
+
+    let count = |severity| {
+        site_findings
+            .iter()
+            .filter(|f| f.finding.severity == severity)
+            .count()
+    };
+    common.print_chatter(&format!(
+        "Linted {} sites: {} errors, {} warnings, {} info",
+        quirks_parsed.len(),
+        count(Severity::Error),
+        count(Severity::Warning),
+        count(Severity::Info)
+    ));
+
+    let failing = site_findings
+        .iter()
+        .filter(|f| f.finding.severity >= fail_on)
+        .count();
+    if failing > 0 {
+        return Err(anyhow!(
+            "{} findings were at least as severe as `{}`",
+            failing,
+            fail_on
+        ));
+    }
+
+    Ok(())
+}
+
+/// Generate `count` example passwords for every rule in `quirks_parsed`.
+///
+/// Errors if any rule fails to parse or can't be satisfied.
+fn generate_passwords(
+    quirks_parsed: &BTreeMap<String, Quirk>,
+    count: usize,
+    mode: GenerateMode,
+    common: &CommonOpts,
+) -> Result<(), anyhow::Error> {
+    let mut rng = rand::thread_rng();
+    // Report a password that doesn't satisfy the rules rather than printing it
+    let options = GenerateOptions {
+        mode,
+        self_check: true,
+        ..GenerateOptions::default()
+    };
+    let mut generated = Vec::new();
+    let mut failed_to_parse = 0;
+    let mut failed_to_generate = 0;
+
+    for (site, quirk) in quirks_parsed.iter() {
+        let parsed = match parse_rules(&quirk.password_rules, common.strict) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                report_parse_error(site, &quirk.password_rules, e, common);
+                failed_to_parse += 1;
+                continue;
+            }
+        };
+
+        let passwords: Result<Vec<String>, GenerateError> = (0..count)
+            .map(|_| parsed.generate_with(&options, &mut rng))
+            .collect();
+        let (passwords, error) = match passwords {
+            Ok(passwords) => (passwords, None),
+            Err(e) => {
+                failed_to_generate += 1;
+                (Vec::new(), Some(e.to_string()))
+            }
+        };
+
+        if common.format == Format::Text {
+            match error.as_ref() {
+                Some(error) => outln!(
+                    "{}\n",
+                    format!("{}: can't generate a password: {}", site, error).red()
+                ),
+                None => {
+                    outln!("{}:", site);
+                    for password in passwords.iter() {
+                        outln!("  {}", password);
+                    }
+                    outln!();
+                }
+            }
+        }
+
+        generated.push(GeneratedPasswords {
+            site: site.clone(),
+            passwords,
+            error,
+        });
+    }
+
+    common.format.print_serialized(&generated)?;
+
+    if failed_to_parse > 0 {
+        return Err(anyhow!(
+            "{} password rules failed to parse",
+            failed_to_parse
+        ));
+    }
+    if failed_to_generate > 0 {
+        return Err(anyhow!(
+            "Passwords couldn't be generated for {} password rules",
+            failed_to_generate
+        ));
+    }
+
+    Ok(())
+}
+
+fn main() -> Result<(), anyhow::Error> {
+    let opt = Opt::from_args();
+
+    let common = match &opt {
+        Opt::Check { common, .. }
+        | Opt::Diff { common, .. }
+        | Opt::Stats { common, .. }
+        | Opt::Group { common, .. }
+        | Opt::Explain { common, .. }
+        | Opt::Validate { common, .. }
+        | Opt::Lint { common, .. }
+        | Opt::Generate { common, .. }
+        | Opt::Export { common, .. } => common,
+    };
+    let csv_supported = match &opt {
+        Opt::Check { file_name, .. } => !file_name.as_ref().is_some_and(|path| path.is_dir()),
+        Opt::Diff { .. } => true,
+        _ => false,
+    };
+    if common.format == Format::Csv && !csv_supported {
+        return Err(anyhow!(
+            "`--format csv` is only supported when checking or diffing a single file"
+        ));
+    }
+
+    if let Some(output) = common.output.as_ref() {
+        let file = fs::File::create(output)
+            .with_context(|| format!("Failed to create {}", output.to_string_lossy()))?;
+        *OUTPUT_FILE.lock().unwrap() = Some(BufWriter::new(file));
+    }
+    // This also controls the colors used when pretty-printing parse errors
+    colored::control::set_override(
+        common
+            .color
+            .enabled(common.output.is_none() && io::stdout().is_terminal()),
+    );
+
+    let result = run(opt);
+    if let Some(e) = OUTPUT_ERROR.lock().unwrap().take() {
+        return Err(e);
+    }
+    if let Some(file) = OUTPUT_FILE.lock().unwrap().as_mut() {
+        file.flush().context("Failed to write the output file")?;
+    }
+    result
+}
+
+fn run(opt: Opt) -> Result<(), anyhow::Error> {
+    match opt {
+        Opt::Check {
+            rule: Some(rule),
+            common,
+            ..
+        } => {
+            if common.format == Format::Text {
+                if let Ok(parsed) = parse_rules(&rule, common.strict) {
+                    outln!("{:#?}", parsed);
+                }
+            }
+
+            let quirks_parsed = BTreeMap::from([(
+                "rule".to_string(),
+                Quirk {
+                    password_rules: rule,
+                },
+            )]);
+            let cache = ParseCache::new(quirks_parsed.values(), common.strict);
+            let site_reports = check_quirks(&quirks_parsed, &cache, &common);
+            common.format.print_site_reports(&site_reports)?;
+            let counts = CheckCounts::from_reports(&site_reports);
+            counts.print(&common);
+
+            if counts.failed_to_parse > 0 {
+                return Err(anyhow!("The password rule failed to parse"));
+            }
+        }
+        Opt::Check {
+            file_name,
+            fix,
+            common,
+            ..
+        } => {
+            // structopt requires either a file name or a rule, and the rule was handled above
+            let file_name = file_name.unwrap();
+
+            if fix && is_stdin(&file_name) {
+                return Err(anyhow!("Rules read from stdin can't be fixed in place"));
+            }
+
+            if file_name.is_dir() {
+                return check_directory(&file_name, fix, &common);
+            }
+
+            let (counts, site_reports) = check_file(&file_name, fix, &common)?;
+            common.format.print_site_reports(&site_reports)?;
+            counts.print(&common);
 
-            println!("Checking {}", site);
+            if counts.failed_to_parse > 0 {
+                return Err(anyhow!("Some password rules failed to parse"));
+            }
+        }
+        Opt::Diff {
+            file_name,
+            diff_against,
+            strict_allowed,
+            ordered_allowed,
+            common,
+        } => {
+            if is_stdin(&file_name) && is_stdin(&diff_against) {
+                return Err(anyhow!(
+                    "Only one of the files being diffed can be read from stdin"
+                ));
+            }
 
-            assert_eq!(quirk_parsed.min_length, other_quirk_parsed.min_length);
-            assert_eq!(quirk_parsed.max_length, other_quirk_parsed.max_length);
-            assert_eq!(
-                quirk_parsed.max_consecutive,
-                other_quirk_parsed.max_consecutive
+            let mut quirks_parsed = load_rules_map(&file_name)?;
+            let mut quirks_to_diff_parsed = load_rules_map(&diff_against)?;
+            if let Some(site) = common.site.as_ref() {
+                retain_site(&mut quirks_parsed, site, &file_name)?;
+                retain_site(&mut quirks_to_diff_parsed, site, &diff_against)?;
+            }
+
+            // Both files are parsed up front, so the diff reuses what the check parsed
+            let cache = ParseCache::new(
+                quirks_parsed.values().chain(quirks_to_diff_parsed.values()),
+                common.strict,
             );
-            assert_eq!(quirk_parsed.allowed, other_quirk_parsed.allowed);
-
-            {
-                // Clone the required classes so removing them doesn't affect the subsequent
-                // loop
-                let mut other_quirk_required = other_quirk_parsed.required.clone();
-                for required_class in quirk_parsed.required.iter() {
-                    assert!(other_quirk_required.contains(required_class));
-                    // Remove the class so it can't be matched again
-                    other_quirk_required.remove(
-                        other_quirk_required
-                            .iter()
-                            .enumerate()
-                            .find(|(_, c)| *c == required_class)
-                            .map(|(i, _)| i)
-                            .unwrap(),
-                    );
+            let site_reports = check_quirks(&quirks_parsed, &cache, &common);
+            // The JSON and YAML output is the diff report, which has to be the only document
+            if common.format == Format::Csv {
+                common.format.print_site_reports(&site_reports)?;
+            }
+            let counts = CheckCounts::from_reports(&site_reports);
+            counts.print(&common);
+
+            if counts.failed_to_parse > 0 {
+                return Err(anyhow!("Some password rules failed to parse"));
+            }
+
+            let options = DiffOptions {
+                strict_allowed,
+                ordered_allowed,
+            };
+            diff_quirks(
+                &quirks_parsed,
+                &quirks_to_diff_parsed,
+                &diff_against,
+                &cache,
+                &options,
+                &common,
+            )?;
+        }
+        Opt::Stats { file_name, common } => {
+            let mut quirks_parsed = load_rules_map(&file_name)?;
+            if let Some(site) = common.site.as_ref() {
+                retain_site(&mut quirks_parsed, site, &file_name)?;
+            }
+
+            print_stats(&quirks_parsed, &common)?;
+        }
+        Opt::Group { file_name, common } => {
+            let mut quirks_parsed = load_rules_map(&file_name)?;
+            if let Some(site) = common.site.as_ref() {
+                retain_site(&mut quirks_parsed, site, &file_name)?;
+            }
+
+            group_quirks(&quirks_parsed, &common)?;
+        }
+        Opt::Explain { file_name, common } => {
+            let mut quirks_parsed = load_rules_map(&file_name)?;
+            if let Some(site) = common.site.as_ref() {
+                retain_site(&mut quirks_parsed, site, &file_name)?;
+            }
+
+            explain_quirks(&quirks_parsed, &common)?;
+        }
+        Opt::Validate {
+            file_name,
+            password,
+            common,
+        } => {
+            let site = common
+                .site
+                .as_ref()
+                .ok_or_else(|| anyhow!("`--site` is required to validate a password"))?;
+            let mut quirks_parsed = load_rules_map(&file_name)?;
+            retain_site(&mut quirks_parsed, site, &file_name)?;
+
+            let password_rules = &quirks_parsed[site].password_rules;
+            let parsed = match parse_rules(password_rules, common.strict) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    report_parse_error(site, password_rules, e, &common);
+                    return Err(anyhow!("The password rule failed to parse"));
                 }
+            };
+
+            let report = parsed.check(&password);
+            match common.format {
+                Format::Text => out!("{}", report),
+                format => format.print_serialized(&PasswordReport {
+                    site: site.clone(),
+                    valid: report.is_valid(),
+                    violations: report.violations.iter().map(|v| v.to_string()).collect(),
+                })?,
             }
 
-            for required_class in other_quirk_parsed.required.iter() {
-                assert!(quirk_parsed.required.contains(required_class));
-                // Remove the class so it can't be matched again
-                quirk_parsed.required.remove(
-                    quirk_parsed
-                        .required
-                        .iter()
-                        .enumerate()
-                        .find(|(_, c)| *c == required_class)
-                        .map(|(i, _)| i)
-                        .unwrap(),
-                );
+            if !report.is_valid() {
+                return Err(anyhow!(
+                    "The password doesn't satisfy the rules for {}",
+                    site
+                ));
             }
         }
+        Opt::Lint {
+            file_name,
+            fail_on,
+            allowed_lints,
+            lint_config,
+            common,
+        } => {
+            let mut config = match lint_config {
+                Some(path) => load_lint_config(&path)?,
+                None => LintConfig::default(),
+            };
+            for name in allowed_lints {
+                config.lints.insert(name, LintLevel::Allow);
+            }
+            if let Some(name) = config.unknown_lints().first() {
+                return Err(anyhow!(
+                    "Unknown lint `{}`; expected one of: {}",
+                    name,
+                    lint::LINTS.join(", ")
+                ));
+            }
+            let fail_on = fail_on.or(config.fail_on).unwrap_or(Severity::Error);
 
-        println!("All rules were semantically equivalent!");
+            let mut quirks_parsed = load_rules_map(&file_name)?;
+            if let Some(site) = common.site.as_ref() {
+                retain_site(&mut quirks_parsed, site, &file_name)?;
+            }
+
+            lint_quirks(&quirks_parsed, fail_on, &config, &common)?;
+        }
+        Opt::Generate {
+            file_name,
+            count,
+            mode,
+            common,
+        } => {
+            let mut quirks_parsed = load_rules_map(&file_name)?;
+            if let Some(site) = common.site.as_ref() {
+                retain_site(&mut quirks_parsed, site, &file_name)?;
+            }
+
+            generate_passwords(&quirks_parsed, count, mode, &common)?;
+        }
+        Opt::Export { file_name, common } => {
+            let mut quirks_parsed = load_rules_map(&file_name)?;
+            if let Some(site) = common.site.as_ref() {
+                retain_site(&mut quirks_parsed, site, &file_name)?;
+            }
+
+            export_quirks(&quirks_parsed, &common)?;
+        }
     }
 
     Ok(())
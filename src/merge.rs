@@ -0,0 +1,119 @@
+//! Combining layered password rules into a single effective set of rules
+
+use crate::class::{canonical_classes, classes_from_chars, CharacterClassExt};
+use crate::defaults::Defaults;
+use crate::rules::PasswordRulesExt;
+use password_rules_parser::{CharacterClass, PasswordRules};
+
+/// The stricter of two optional limits, where `stricter` picks between two present limits
+fn stricter(a: Option<u32>, b: Option<u32>, stricter: fn(u32, u32) -> u32) -> Option<u32> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(stricter(a, b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// The classes a password may draw from under `rules`: its `allowed` and `required` classes,
+/// with Apple's defaults filled in
+fn permitted(rules: &PasswordRules) -> Vec<CharacterClass> {
+    let rules = rules.with_defaults();
+    rules
+        .allowed
+        .iter()
+        .chain(rules.required_classes())
+        .cloned()
+        .collect()
+}
+
+/// The classes containing the characters that both `rules` and `other` permit, or `None` if
+/// they don't have any characters in common
+fn intersect_permitted(
+    rules: &PasswordRules,
+    other: &PasswordRules,
+) -> Option<Vec<CharacterClass>> {
+    let (permitted, other_permitted) = (permitted(rules), permitted(other));
+
+    let is_unicode = |classes: &[CharacterClass]| classes.contains(&CharacterClass::Unicode);
+    match (is_unicode(&permitted), is_unicode(&other_permitted)) {
+        (true, true) => return Some(vec![CharacterClass::Unicode]),
+        (true, false) => return Some(classes_from_chars(&all_chars(&other_permitted))),
+        (false, true) => return Some(classes_from_chars(&all_chars(&permitted))),
+        _ => {}
+    }
+
+    let chars: Vec<char> = all_chars(&permitted)
+        .into_iter()
+        .filter(|&c| other_permitted.iter().any(|class| class.contains(c)))
+        .collect();
+    if chars.is_empty() {
+        None
+    } else {
+        Some(classes_from_chars(&chars))
+    }
+}
+
+/// Every character in `classes`, none of which may be `unicode`
+fn all_chars(classes: &[CharacterClass]) -> Vec<char> {
+    classes.iter().flat_map(|class| class.chars()).collect()
+}
+
+/// Extension methods for combining `PasswordRules`
+pub trait Merge {
+    /// Combine two sets of rules into one with the stricter of each of their properties, except
+    /// that the characters either set of rules requires stay allowed:
+    ///
+    /// * `min_length` is the larger of the two minimum lengths
+    /// * `max_length` and `max_consecutive` are the smaller of the two limits
+    /// * `required` contains every group that's required by either set of rules, once (groups
+    ///   are compared as [`PasswordRulesExt::normalize_required`] compares them, and the first
+    ///   of any duplicates is kept as written)
+    /// * `allowed` contains the characters that both sets of rules permit, counting the
+    ///   characters each one requires and Apple's default of `ascii-printable` (see
+    ///   [`Defaults::with_defaults`])
+    ///
+    /// A limit that's only set in one of the rules is kept as is. Returns `None` if no character
+    /// is permitted by both sets of rules, since no password could satisfy them both.
+    ///
+    /// Required characters are implicitly allowed, so the merged rules permit every character
+    /// that either set of rules requires, even one the other set rejects: merging
+    /// `allowed: lower;` with `required: digit; allowed: lower;` permits digits.
+    ///
+    /// ```
+    /// use password_rules_checker::Merge;
+    /// use password_rules_parser::{parse_password_rules, CharacterClass};
+    ///
+    /// let base = parse_password_rules("minlength: 8; allowed: lower, digit;", true).unwrap();
+    /// let site = parse_password_rules("minlength: 6; maxlength: 16; allowed: lower;", true).unwrap();
+    /// let merged = base.merge(&site).unwrap();
+    ///
+    /// assert_eq!(merged.min_length, Some(8));
+    /// assert_eq!(merged.max_length, Some(16));
+    /// assert_eq!(merged.allowed, vec![CharacterClass::Lower]);
+    ///
+    /// let digits = parse_password_rules("allowed: digit;", true).unwrap();
+    /// assert_eq!(site.merge(&digits), None);
+    /// ```
+    fn merge(&self, other: &PasswordRules) -> Option<PasswordRules>;
+}
+
+impl Merge for PasswordRules {
+    fn merge(&self, other: &PasswordRules) -> Option<PasswordRules> {
+        let mut required = Vec::new();
+        let mut seen = Vec::new();
+        for classes in self.required.iter().chain(other.required.iter()) {
+            let canonical = canonical_classes(classes);
+            if !seen.contains(&canonical) {
+                seen.push(canonical);
+                required.push(classes.clone());
+            }
+        }
+
+        Some(PasswordRules {
+            min_length: stricter(self.min_length, other.min_length, u32::max),
+            max_length: stricter(self.max_length, other.max_length, u32::min),
+            max_consecutive: stricter(self.max_consecutive, other.max_consecutive, u32::min),
+            allowed: intersect_permitted(self, other)?,
+            required,
+        })
+    }
+}
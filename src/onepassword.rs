@@ -0,0 +1,172 @@
+//! Exporting password rules to a JSON approximation of 1Password's password recipes
+//!
+//! 1Password's generator builds passwords from a length and a handful of character sets rather
+//! than from Apple's classes, and it doesn't publish a schema for importing rules, so this is a
+//! close approximation that an importer can map onto a recipe. Most of the rules language maps
+//! cleanly:
+//!
+//! * `minlength` and `maxlength` become the `length` bounds, with `null` for no bound.
+//! * `upper`, `lower`, `digit`, and `special` become the `uppercase`, `lowercase`, `digits`, and
+//!   `symbols` sets, and `ascii-printable` is all four of them. Apple's `special` includes the
+//!   space, so an importer whose symbol set leaves it out loses that one character.
+//! * Custom classes become a `custom` set with their characters.
+//! * Each `required` group becomes a list of sets, any one of which satisfies it.
+//! * `max-consecutive` is kept as `maxConsecutive`.
+//!
+//! `unicode` has no clean mapping, since the sets are all ASCII, so it's exported as all four sets
+//! and a note is added to the recipe's `notes`, so an importer can tell which recipes need
+//! checking by hand.
+
+use crate::defaults::Defaults;
+use crate::rules::PasswordRulesExt;
+use password_rules_parser::{CharacterClass, PasswordRules};
+use serde::Serialize;
+
+/// A set of characters a 1Password recipe can draw from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OnePasswordSet {
+    Uppercase,
+    Lowercase,
+    Digits,
+    Symbols,
+    /// The characters of every custom class in a property, in the order they first appear
+    Custom(String),
+}
+
+/// The sets `ascii-printable` (and, approximately, `unicode`) is exported as
+const ASCII_PRINTABLE_SETS: [OnePasswordSet; 4] = [
+    OnePasswordSet::Uppercase,
+    OnePasswordSet::Lowercase,
+    OnePasswordSet::Digits,
+    OnePasswordSet::Symbols,
+];
+
+/// The length bounds of a recipe
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LengthRange {
+    pub min: Option<u32>,
+    pub max: Option<u32>,
+}
+
+/// Password rules converted for 1Password; see the [module docs](self) for how each property is
+/// mapped
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnePasswordRecipe {
+    pub length: LengthRange,
+    /// Every set a password may draw from, including the sets of the `required` groups (which
+    /// Apple's rules permit even when they aren't in `allowed`)
+    pub allowed: Vec<OnePasswordSet>,
+    /// The sets of each `required` group; a password needs a character from at least one set in
+    /// each group
+    pub required: Vec<Vec<OnePasswordSet>>,
+    pub max_consecutive: Option<u32>,
+    /// The parts of the rules that couldn't be converted exactly, and how they were approximated
+    pub notes: Vec<String>,
+}
+
+/// Convert `classes` to sets, merging every custom class into a single `custom` set. Notes are
+/// added to `notes` for classes that can only be approximated.
+fn sets_for(classes: &[CharacterClass], notes: &mut Vec<String>) -> Vec<OnePasswordSet> {
+    let mut sets: Vec<OnePasswordSet> = Vec::new();
+    let mut custom = String::new();
+
+    for class in classes.iter() {
+        let class_sets = match class {
+            CharacterClass::Upper => vec![OnePasswordSet::Uppercase],
+            CharacterClass::Lower => vec![OnePasswordSet::Lowercase],
+            CharacterClass::Digit => vec![OnePasswordSet::Digits],
+            CharacterClass::Special => vec![OnePasswordSet::Symbols],
+            CharacterClass::AsciiPrintable => ASCII_PRINTABLE_SETS.to_vec(),
+            CharacterClass::Unicode => {
+                let note = "`unicode` has no equivalent set, so it was exported as every ASCII set"
+                    .to_string();
+                if !notes.contains(&note) {
+                    notes.push(note);
+                }
+                ASCII_PRINTABLE_SETS.to_vec()
+            }
+            CharacterClass::Custom(chars) => {
+                for &c in chars.iter() {
+                    if !custom.contains(c) {
+                        custom.push(c);
+                    }
+                }
+                Vec::new()
+            }
+        };
+        for set in class_sets {
+            if !sets.contains(&set) {
+                sets.push(set);
+            }
+        }
+    }
+
+    if !custom.is_empty() {
+        sets.push(OnePasswordSet::Custom(custom));
+    }
+    sets
+}
+
+/// Extension methods for exporting `PasswordRules` to 1Password
+pub trait ToOnePassword {
+    /// Convert the rules to a [`OnePasswordRecipe`].
+    ///
+    /// The rules are converted with their defaults filled in (see [`Defaults::with_defaults`])
+    /// and with duplicate `required` groups removed.
+    ///
+    /// ```
+    /// use password_rules_checker::onepassword::{OnePasswordSet, ToOnePassword};
+    /// use password_rules_parser::parse_password_rules;
+    ///
+    /// let rules = parse_password_rules(
+    ///     "minlength: 8; maxlength: 64; required: upper; required: digit, [-_]; allowed: lower;",
+    ///     false,
+    /// )
+    /// .unwrap();
+    /// let recipe = rules.to_onepassword();
+    ///
+    /// assert_eq!((recipe.length.min, recipe.length.max), (Some(8), Some(64)));
+    /// assert_eq!(
+    ///     recipe.required,
+    ///     vec![
+    ///         vec![OnePasswordSet::Uppercase],
+    ///         vec![OnePasswordSet::Digits, OnePasswordSet::Custom("-_".to_string())],
+    ///     ]
+    /// );
+    /// assert!(recipe.notes.is_empty());
+    /// ```
+    fn to_onepassword(&self) -> OnePasswordRecipe;
+}
+
+impl ToOnePassword for PasswordRules {
+    fn to_onepassword(&self) -> OnePasswordRecipe {
+        let rules = self.with_defaults().normalize_required();
+        let mut notes = Vec::new();
+
+        let permitted: Vec<CharacterClass> = rules
+            .allowed
+            .iter()
+            .chain(rules.required_classes())
+            .cloned()
+            .collect();
+        let allowed = sets_for(&permitted, &mut notes);
+        let required = rules
+            .required
+            .iter()
+            .map(|classes| sets_for(classes, &mut notes))
+            .collect();
+
+        OnePasswordRecipe {
+            length: LengthRange {
+                min: rules.min_length,
+                max: rules.max_length,
+            },
+            allowed,
+            required,
+            max_consecutive: rules.max_consecutive,
+            notes,
+        }
+    }
+}
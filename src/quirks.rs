@@ -0,0 +1,169 @@
+//! Loading the quirks file from Apple's password manager resources, which maps each site to its
+//! password rules
+
+use serde::de::{MapAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+/// The quirks for a single site
+#[derive(Debug, Clone, Deserialize)]
+pub struct Quirk {
+    /// The site's rules, in the rules language
+    #[serde(rename = "password-rules")]
+    pub password_rules: String,
+}
+
+/// The entries of a quirks file in the order they're written, including entries with duplicate
+/// keys (which deserializing straight into a map would collapse)
+#[derive(Debug, Clone)]
+pub struct QuirkEntries(pub Vec<(String, Quirk)>);
+
+impl<'de> Deserialize<'de> for QuirkEntries {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EntriesVisitor;
+
+        impl<'de> Visitor<'de> for EntriesVisitor {
+            type Value = QuirkEntries;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.write_str("a map of sites to their quirks")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut entries = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(QuirkEntries(entries))
+            }
+        }
+
+        deserializer.deserialize_map(EntriesVisitor)
+    }
+}
+
+impl QuirkEntries {
+    /// Every pair of site keys that refer to the same host when compared case-insensitively, in
+    /// the order they're written. Keys that are exact duplicates are included.
+    pub fn colliding_keys(&self) -> Vec<(&str, &str)> {
+        let mut seen: HashMap<String, Vec<&str>> = HashMap::new();
+        let mut collisions = Vec::new();
+        for (site, _) in self.0.iter() {
+            let spellings = seen.entry(site.to_lowercase()).or_default();
+            collisions.extend(spellings.iter().map(|&earlier| (earlier, site.as_str())));
+            spellings.push(site);
+        }
+        collisions
+    }
+
+    /// The quirks keyed by site. As when deserializing a map, the last of any entries with the
+    /// same key wins.
+    pub fn into_map(self) -> BTreeMap<String, Quirk> {
+        self.0.into_iter().collect()
+    }
+}
+
+/// The error returned when a quirks file can't be loaded
+#[derive(Debug)]
+pub enum LoadError {
+    /// The file couldn't be read
+    Read { path: PathBuf, source: io::Error },
+    /// The file isn't a JSON map of sites to their quirks
+    Parse {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Read { path, .. } => {
+                write!(f, "Failed to read file at {}", path.to_string_lossy())
+            }
+            LoadError::Parse { path, .. } => write!(
+                f,
+                "Failed to parse JSON loaded from {}",
+                path.to_string_lossy()
+            ),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoadError::Read { source, .. } => Some(source),
+            LoadError::Parse { source, .. } => Some(source),
+        }
+    }
+}
+
+/// Read the entries of a quirks file from `reader`, in the order they're written.
+///
+/// The JSON is parsed as it's read rather than being read into memory first, which keeps the
+/// peak memory use down for large files. Wrap unbuffered readers, such as a `File`, in a
+/// `BufReader`.
+pub fn read_quirk_entries(reader: impl Read) -> serde_json::Result<QuirkEntries> {
+    serde_json::from_reader(reader)
+}
+
+/// Load the entries of the quirks file at `path`, in the order they're written.
+///
+/// The file is parsed as it's read; see [`read_quirk_entries`].
+pub fn load_quirk_entries(path: impl AsRef<Path>) -> Result<QuirkEntries, LoadError> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|source| LoadError::Read {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    read_quirk_entries(BufReader::new(file)).map_err(|source| {
+        // Reading can also fail partway through the file
+        if source.is_io() {
+            LoadError::Read {
+                path: path.to_path_buf(),
+                source: source.into(),
+            }
+        } else {
+            LoadError::Parse {
+                path: path.to_path_buf(),
+                source,
+            }
+        }
+    })
+}
+
+/// Load the quirks in the file at `path`, keyed (and therefore ordered) by site
+pub fn load_rules_map(path: impl AsRef<Path>) -> Result<BTreeMap<String, Quirk>, LoadError> {
+    Ok(load_quirk_entries(path)?.into_map())
+}
+
+/// Like [`load_rules_map`], but reading the file with async IO, for use in async services.
+///
+/// Deserializing a large file can take a while, so it's done on tokio's blocking thread pool
+/// rather than on the calling task. This must be called from within a tokio runtime.
+#[cfg(feature = "async")]
+pub async fn load_rules_map_async(
+    path: impl AsRef<Path>,
+) -> Result<BTreeMap<String, Quirk>, LoadError> {
+    let path = path.as_ref().to_path_buf();
+    let json_string = match tokio::fs::read_to_string(&path).await {
+        Ok(json_string) => json_string,
+        Err(source) => return Err(LoadError::Read { path, source }),
+    };
+
+    tokio::task::spawn_blocking(move || {
+        serde_json::from_str::<QuirkEntries>(&json_string)
+            .map(QuirkEntries::into_map)
+            .map_err(|source| LoadError::Parse { path, source })
+    })
+    .await
+    // Only a panic while deserializing can make the task fail, so pass the panic on
+    .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()))
+}
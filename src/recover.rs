@@ -0,0 +1,100 @@
+//! Parsing password rules while recovering from errors
+
+use crate::error::location;
+use crate::syntax;
+use password_rules_parser::error::PasswordRulesError;
+use password_rules_parser::{parse_password_rules, CharacterClass, PasswordRules};
+
+/// `s` with the rewrites [`parse_password_rules_lenient`] makes before parsing. The rewrites keep
+/// offsets, so errors from parsing the result point into `s`.
+fn lenient_rules(s: &str) -> String {
+    syntax::literal_trailing_hyphens(&syntax::blank_empty_clauses(s))
+}
+
+/// Parse `s` like [`parse_password_rules_lenient`], but keep going after a clause fails to parse.
+///
+/// Every clause is parsed on its own, and the errors for each clause that fails to parse are
+/// returned, with locations relative to the whole of `s` (after trimming it, as the parser does)
+/// and columns counted in characters. The returned rules are the result of parsing only the
+/// clauses that succeeded, so they're a best effort at what `s` was meant to say. If every
+/// clause parses, this returns the same rules as `parse_password_rules_lenient` and no errors.
+pub fn parse_password_rules_recover(
+    s: &str,
+    supply_default: bool,
+) -> (PasswordRules, Vec<PasswordRulesError>) {
+    let lenient = lenient_rules(s);
+    let error = match parse_password_rules(&lenient, supply_default) {
+        Ok(rules) => return (rules, Vec::new()),
+        Err(e) => e,
+    };
+
+    let mut parsed_clauses = Vec::new();
+    let mut errors = Vec::new();
+    // Like the parser, report locations relative to the trimmed input. Blanking empty clauses can
+    // turn leading semicolons into whitespace, so trim by where `s` itself starts and ends.
+    let start = s.len() - s.trim_start().len();
+    let trimmed = &lenient[start..start + s.trim().len()];
+    for clause in syntax::split_clauses(trimmed) {
+        // The clauses are slices of `trimmed`
+        let offset = clause.as_ptr() as usize - trimmed.as_ptr() as usize;
+
+        match parse_password_rules(clause, false) {
+            Ok(_) => parsed_clauses.push(clause),
+            Err(mut e) => {
+                for expected in e.expectations.iter_mut() {
+                    expected.index += offset;
+                    let (line, column) = location(trimmed, expected.index);
+                    expected.line = line;
+                    expected.column = column;
+                }
+                errors.push(e);
+            }
+        }
+    }
+
+    // Clauses can only fail to parse together if the string as a whole is malformed, such as
+    // when it's empty
+    if errors.is_empty() {
+        errors.push(error);
+    }
+
+    let rules =
+        parse_password_rules(&parsed_clauses.join("; "), supply_default).unwrap_or_else(|_| {
+            let mut rules = PasswordRules::default();
+            if supply_default {
+                rules.allowed.push(CharacterClass::AsciiPrintable);
+            }
+            rules
+        });
+
+    (rules, errors)
+}
+
+/// Parse `s` like `parse_password_rules`, but tolerate empty clauses, such as those left by a
+/// doubled (`minlength: 8;;`) or leading (`;minlength: 8`) semicolon, and read a `-` at the end of
+/// a custom class (`[abc-]`) as a literal hyphen, like one at the start.
+///
+/// Error locations are relative to `s`, as they would be from `parse_password_rules`.
+///
+/// ```
+/// use password_rules_checker::parse_password_rules_lenient;
+/// use password_rules_parser::parse_password_rules;
+///
+/// assert!(parse_password_rules("minlength: 8;;", false).is_err());
+/// assert_eq!(
+///     parse_password_rules_lenient("minlength: 8;;", false).unwrap(),
+///     parse_password_rules("minlength: 8;", false).unwrap()
+/// );
+///
+/// assert!(parse_password_rules("allowed: [abc-];", false).is_err());
+/// assert_eq!(
+///     parse_password_rules_lenient("allowed: [abc-];", false).unwrap(),
+///     parse_password_rules("allowed: [-abc];", false).unwrap()
+/// );
+/// ```
+pub fn parse_password_rules_lenient(
+    s: &str,
+    supply_default: bool,
+) -> Result<PasswordRules, PasswordRulesError> {
+    parse_password_rules(&lenient_rules(s), supply_default)
+}
@@ -0,0 +1,173 @@
+//! General-purpose extension methods for the parser's `PasswordRules`
+
+use crate::class::{canonical_classes, classes_from_chars, CharacterClassExt};
+use password_rules_parser::{CharacterClass, PasswordRules};
+use std::iter::Flatten;
+use std::slice;
+
+/// An iterator over every class in every `required` group; see
+/// [`PasswordRulesExt::required_classes`]
+pub type RequiredClasses<'a> = Flatten<slice::Iter<'a, Vec<CharacterClass>>>;
+
+/// Extension methods for `PasswordRules`
+pub trait PasswordRulesExt {
+    /// Every class in every `required` group, in order.
+    ///
+    /// This loses the group boundaries, which matter for what's actually required (a password
+    /// needs a character from at least one class in *each* group), so use `required` directly
+    /// when that matters.
+    ///
+    /// ```
+    /// use password_rules_checker::PasswordRulesExt;
+    /// use password_rules_parser::{parse_password_rules, CharacterClass};
+    ///
+    /// let rules = parse_password_rules("required: upper, digit; required: lower;", false).unwrap();
+    ///
+    /// assert_eq!(
+    ///     rules.required_classes().collect::<Vec<_>>(),
+    ///     vec![&CharacterClass::Upper, &CharacterClass::Digit, &CharacterClass::Lower]
+    /// );
+    /// ```
+    fn required_classes(&self) -> RequiredClasses<'_>;
+
+    /// The rules with duplicate `required` groups removed, and each group's classes
+    /// canonicalized, sorted, and de-duplicated.
+    ///
+    /// Groups are compared regardless of the order of their classes, and the first of any
+    /// duplicate groups is kept. The meaning of the rules doesn't change. This isn't applied
+    /// when parsing, so parsed rules keep every group as written; [`crate::diff::diff_rules`]
+    /// applies it before comparing.
+    ///
+    /// ```
+    /// use password_rules_checker::PasswordRulesExt;
+    /// use password_rules_parser::parse_password_rules;
+    ///
+    /// let rules = parse_password_rules("required: digit; required: upper; required: digit;", false)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     rules.normalize_required(),
+    ///     parse_password_rules("required: digit; required: upper;", false).unwrap()
+    /// );
+    /// ```
+    fn normalize_required(&self) -> PasswordRules;
+
+    /// The rules with the `allowed` classes collapsed into the fewest classes that allow exactly
+    /// the same characters.
+    ///
+    /// The equivalences used are:
+    ///
+    /// * `upper`, `lower`, `digit`, and `special` together are `ascii-printable`, since `special`
+    ///   includes the space
+    /// * Every class is contained in `unicode`, and every class other than `unicode` and custom
+    ///   classes with non-ASCII characters is contained in `ascii-printable`
+    /// * A custom class containing every character of a named class is that named class plus
+    ///   the rest of its characters
+    ///
+    /// Parsed rules are already collapsed, since the parser merges every character in a property
+    /// into as few classes as it can; this is for rules built or changed some other way.
+    ///
+    /// ```
+    /// use password_rules_checker::PasswordRulesExt;
+    /// use password_rules_parser::{CharacterClass, PasswordRules};
+    ///
+    /// let rules = PasswordRules {
+    ///     allowed: vec![
+    ///         CharacterClass::Upper,
+    ///         CharacterClass::Lower,
+    ///         CharacterClass::Digit,
+    ///         CharacterClass::Special,
+    ///     ],
+    ///     ..PasswordRules::default()
+    /// };
+    ///
+    /// assert_eq!(rules.collapse_allowed().allowed, vec![CharacterClass::AsciiPrintable]);
+    /// ```
+    fn collapse_allowed(&self) -> PasswordRules;
+
+    /// The rules with every custom class in `allowed` and `required` converted to Unicode
+    /// Normalization Form C; see [`CharacterClassExt::to_nfc`].
+    ///
+    /// The parser drops non-ASCII characters from custom classes, so this only changes rules
+    /// that were constructed some other way, such as by deserializing them.
+    #[cfg(feature = "nfc")]
+    fn to_nfc(&self) -> PasswordRules;
+}
+
+impl PasswordRulesExt for PasswordRules {
+    fn required_classes(&self) -> RequiredClasses<'_> {
+        self.required.iter().flatten()
+    }
+
+    fn normalize_required(&self) -> PasswordRules {
+        let mut required: Vec<Vec<CharacterClass>> = Vec::new();
+        for classes in self
+            .required
+            .iter()
+            .map(|classes| canonical_classes(classes))
+        {
+            if !required.contains(&classes) {
+                required.push(classes);
+            }
+        }
+
+        PasswordRules {
+            required,
+            ..self.clone()
+        }
+    }
+
+    fn collapse_allowed(&self) -> PasswordRules {
+        let allowed = if self.allowed.contains(&CharacterClass::Unicode) {
+            vec![CharacterClass::Unicode]
+        } else {
+            let chars: Vec<char> = self
+                .allowed
+                .iter()
+                .flat_map(|class| class.chars())
+                .collect();
+            let is_ascii_printable = |&c: &char| CharacterClass::AsciiPrintable.contains(c);
+
+            if CharacterClass::AsciiPrintable
+                .chars()
+                .iter()
+                .all(|c| chars.contains(c))
+            {
+                // Keep `ascii-printable` whole rather than splitting it up to make room for the
+                // non-ASCII characters
+                let mut rest: Vec<char> = chars
+                    .into_iter()
+                    .filter(|c| !is_ascii_printable(c))
+                    .collect();
+                rest.sort_unstable();
+                rest.dedup();
+
+                let mut allowed = vec![CharacterClass::AsciiPrintable];
+                if !rest.is_empty() {
+                    allowed.push(CharacterClass::Custom(rest));
+                }
+                allowed
+            } else {
+                classes_from_chars(&chars)
+            }
+        };
+
+        PasswordRules {
+            allowed,
+            ..self.clone()
+        }
+    }
+
+    #[cfg(feature = "nfc")]
+    fn to_nfc(&self) -> PasswordRules {
+        PasswordRules {
+            allowed: self.allowed.iter().map(|class| class.to_nfc()).collect(),
+            required: self
+                .required
+                .iter()
+                .map(|classes| classes.iter().map(|class| class.to_nfc()).collect())
+                .collect(),
+            ..self.clone()
+        }
+    }
+}
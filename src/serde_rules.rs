@@ -0,0 +1,156 @@
+//! `serde` support for the parser's types, enabled by the `serde-rules` feature.
+//!
+//! The feature only controls this module's API. `serde` is a dependency either way, since the
+//! rest of the crate (the quirks file, lint and diff reports, errors, and statistics) is
+//! serialized with it, so turning the feature off doesn't make the crate any smaller.
+//!
+//! The parser's types don't implement `Serialize` or `Deserialize` themselves, so they're wrapped
+//! in newtypes ([`SerdeRules`] and [`SerdeClass`]) that do:
+//!
+//! ```
+//! use password_rules_checker::serde_rules::SerdeRules;
+//! use password_rules_parser::parse_password_rules;
+//!
+//! let rules = parse_password_rules("minlength: 8; required: digit;", false).unwrap();
+//! let json = serde_json::to_string(&SerdeRules(rules.clone())).unwrap();
+//! let SerdeRules(round_tripped) = serde_json::from_str(&json).unwrap();
+//!
+//! assert_eq!(rules, round_tripped);
+//! ```
+
+use crate::syntax;
+use password_rules_parser::{CharacterClass, PasswordRules};
+use serde::de;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// `PasswordRules` that can be serialized and deserialized.
+///
+/// The serialized form is a map with the same field names as `PasswordRules`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SerdeRules(pub PasswordRules);
+
+impl From<PasswordRules> for SerdeRules {
+    fn from(rules: PasswordRules) -> Self {
+        Self(rules)
+    }
+}
+
+/// The serialized form of `PasswordRules`
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "PasswordRules")]
+struct RulesRepr {
+    #[serde(default)]
+    min_length: Option<u32>,
+    #[serde(default)]
+    max_length: Option<u32>,
+    #[serde(default)]
+    allowed: Vec<SerdeClass>,
+    #[serde(default)]
+    required: Vec<Vec<SerdeClass>>,
+    #[serde(default)]
+    max_consecutive: Option<u32>,
+}
+
+/// A `CharacterClass` that can be serialized and deserialized.
+///
+/// Built-in classes are serialized as the token they're written as in a rules string (such as
+/// `"upper"` or `"ascii-printable"`), and custom classes as their characters wrapped in brackets
+/// (such as `"[-_!]"`). The characters between the brackets are taken literally and keep their
+/// order, so a custom class always round-trips to an equal value.
+///
+/// ```
+/// use password_rules_checker::serde_rules::SerdeClass;
+/// use password_rules_parser::CharacterClass;
+///
+/// let class = SerdeClass(CharacterClass::Custom(vec!['a', ']', '-']));
+/// let json = serde_json::to_string(&class).unwrap();
+///
+/// assert_eq!(json, r#""[a]-]""#);
+/// assert_eq!(serde_json::from_str::<SerdeClass>(&json).unwrap(), class);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SerdeClass(pub CharacterClass);
+
+impl From<CharacterClass> for SerdeClass {
+    fn from(class: CharacterClass) -> Self {
+        Self(class)
+    }
+}
+
+impl Serialize for SerdeClass {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match &self.0 {
+            CharacterClass::Custom(chars) => {
+                let mut s = String::from("[");
+                s.extend(chars.iter());
+                s.push(']');
+                serializer.serialize_str(&s)
+            }
+            class => serializer.serialize_str(&syntax::format_class(class)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SerdeClass {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        if let Some(chars) = s.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            return Ok(Self(CharacterClass::Custom(chars.chars().collect())));
+        }
+
+        let class = match s.to_ascii_lowercase().as_str() {
+            "upper" => CharacterClass::Upper,
+            "lower" => CharacterClass::Lower,
+            "digit" => CharacterClass::Digit,
+            "special" => CharacterClass::Special,
+            "ascii-printable" => CharacterClass::AsciiPrintable,
+            "unicode" => CharacterClass::Unicode,
+            _ => return Err(de::Error::unknown_variant(&s, syntax::CLASS_TOKENS)),
+        };
+        Ok(Self(class))
+    }
+}
+
+impl From<&PasswordRules> for RulesRepr {
+    fn from(rules: &PasswordRules) -> Self {
+        Self {
+            min_length: rules.min_length,
+            max_length: rules.max_length,
+            allowed: rules.allowed.iter().cloned().map(SerdeClass).collect(),
+            required: rules
+                .required
+                .iter()
+                .map(|classes| classes.iter().cloned().map(SerdeClass).collect())
+                .collect(),
+            max_consecutive: rules.max_consecutive,
+        }
+    }
+}
+
+impl From<RulesRepr> for PasswordRules {
+    fn from(rules: RulesRepr) -> Self {
+        Self {
+            min_length: rules.min_length,
+            max_length: rules.max_length,
+            allowed: rules.allowed.into_iter().map(|class| class.0).collect(),
+            required: rules
+                .required
+                .into_iter()
+                .map(|classes| classes.into_iter().map(|class| class.0).collect())
+                .collect(),
+            max_consecutive: rules.max_consecutive,
+        }
+    }
+}
+
+impl Serialize for SerdeRules {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        RulesRepr::from(&self.0).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SerdeRules {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        RulesRepr::deserialize(deserializer).map(|rules| Self(rules.into()))
+    }
+}
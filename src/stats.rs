@@ -0,0 +1,141 @@
+//! Aggregate statistics over the rules in a quirks file
+
+use crate::syntax;
+use password_rules_parser::{CharacterClass, PasswordRules};
+use serde::{Serialize, Serializer};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display, Formatter};
+
+/// The widest a bar in a printed histogram can be
+const MAX_BAR_WIDTH: usize = 50;
+
+/// A count of how many sites use each value of an optional numeric property
+#[derive(Debug, Default)]
+pub struct Histogram {
+    counts: BTreeMap<Option<u32>, usize>,
+}
+
+impl Histogram {
+    pub fn add(&mut self, value: Option<u32>) {
+        *self.counts.entry(value).or_insert(0) += 1;
+    }
+}
+
+impl Display for Histogram {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let max_count = self.counts.values().copied().max().unwrap_or(0);
+
+        for (value, &count) in self.counts.iter() {
+            let value = value.map_or_else(|| "none".to_string(), |v| v.to_string());
+            // Scale the bars down if needed, but make sure every non-zero count gets a bar
+            let width = if max_count > MAX_BAR_WIDTH {
+                (count * MAX_BAR_WIDTH / max_count).max(1)
+            } else {
+                count
+            };
+
+            writeln!(f, "{:>6} | {:<5} {}", value, count, "#".repeat(width))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Serialize for Histogram {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.counts.iter().map(|(value, count)| {
+            (
+                value.map_or_else(|| "none".to_string(), |v| v.to_string()),
+                count,
+            )
+        }))
+    }
+}
+
+/// The most custom classes a `ClassUsage` keeps as examples
+const MAX_CUSTOM_SAMPLES: usize = 5;
+
+/// A count of how many sites use each character class in a property, with every custom class
+/// counted under `custom`
+#[derive(Debug, Default, Serialize)]
+pub struct ClassUsage {
+    counts: BTreeMap<&'static str, usize>,
+    /// A few of the custom classes used, in the rules syntax
+    custom_samples: BTreeSet<String>,
+}
+
+impl ClassUsage {
+    /// Count a site whose property uses `classes`. A class that appears more than once is only
+    /// counted once.
+    pub fn add<'a>(&mut self, classes: impl IntoIterator<Item = &'a CharacterClass>) {
+        let mut names = BTreeSet::new();
+        for class in classes {
+            names.insert(match class {
+                CharacterClass::Upper => "upper",
+                CharacterClass::Lower => "lower",
+                CharacterClass::Digit => "digit",
+                CharacterClass::Special => "special",
+                CharacterClass::AsciiPrintable => "ascii-printable",
+                CharacterClass::Unicode => "unicode",
+                CharacterClass::Custom(_) => {
+                    if self.custom_samples.len() < MAX_CUSTOM_SAMPLES {
+                        self.custom_samples.insert(syntax::format_class(class));
+                    }
+                    "custom"
+                }
+            });
+        }
+
+        for name in names {
+            *self.counts.entry(name).or_insert(0) += 1;
+        }
+    }
+}
+
+impl Display for ClassUsage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // Most used first
+        let mut counts: Vec<_> = self.counts.iter().collect();
+        counts.sort_by_key(|&(_, &count)| Reverse(count));
+
+        for (name, count) in counts {
+            writeln!(f, "{:>15} | {}", name, count)?;
+        }
+        if !self.custom_samples.is_empty() {
+            let samples: Vec<&str> = self.custom_samples.iter().map(String::as_str).collect();
+            writeln!(f, "custom classes include: {}", samples.join(" "))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Statistics gathered across every site in a quirks file
+#[derive(Debug, Default, Serialize)]
+pub struct Stats {
+    pub min_length: Histogram,
+    pub max_length: Histogram,
+    /// How many sites allow each class
+    pub allowed: ClassUsage,
+    /// How many sites require each class, in any `required` group
+    pub required: ClassUsage,
+}
+
+impl Stats {
+    pub fn add(&mut self, rules: &PasswordRules) {
+        self.min_length.add(rules.min_length);
+        self.max_length.add(rules.max_length);
+        self.allowed.add(&rules.allowed);
+        self.required.add(rules.required.iter().flatten());
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "minlength:\n{}", self.min_length)?;
+        writeln!(f, "maxlength:\n{}", self.max_length)?;
+        writeln!(f, "allowed classes:\n{}", self.allowed)?;
+        write!(f, "required classes:\n{}", self.required)
+    }
+}
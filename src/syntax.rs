@@ -0,0 +1,727 @@
+//! Helpers for working with the text of password rules written in Apple's rules language
+
+use crate::error::location;
+use password_rules_parser::error::{Expected, ExpectedAt, PasswordRulesError};
+use password_rules_parser::{CharacterClass, PasswordRules};
+use std::collections::HashSet;
+
+/// Render a character class as the token it would be written as in a rules string
+pub fn format_class(class: &CharacterClass) -> String {
+    match class {
+        CharacterClass::Upper => "upper".to_string(),
+        CharacterClass::Lower => "lower".to_string(),
+        CharacterClass::Digit => "digit".to_string(),
+        CharacterClass::Special => "special".to_string(),
+        CharacterClass::AsciiPrintable => "ascii-printable".to_string(),
+        CharacterClass::Unicode => "unicode".to_string(),
+        CharacterClass::Custom(chars) => {
+            // `-` is only allowed as the first character in a custom class and `]` is only
+            // allowed as the last
+            let mut s = String::from("[");
+            if chars.contains(&'-') {
+                s.push('-');
+            }
+            s.extend(chars.iter().filter(|&&c| c != '-' && c != ']'));
+            if chars.contains(&']') {
+                s.push(']');
+            }
+            s.push(']');
+            s
+        }
+    }
+}
+
+/// Render a list of character classes as the comma-separated list they would be written as in a
+/// rules string
+pub fn format_classes(classes: &[CharacterClass]) -> String {
+    classes
+        .iter()
+        .map(format_class)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Split `s` on every `separator` that isn't inside of a custom character class
+fn split_outside_classes(s: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut part_start = 0;
+    let mut chars = s.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        if c == '[' {
+            // Skip to the end of the custom class. A `]` immediately followed by another `]` is
+            // part of the class rather than the end of it.
+            while let Some((_, c)) = chars.next() {
+                if c == ']' {
+                    if let Some((_, ']')) = chars.peek() {
+                        chars.next();
+                    }
+                    break;
+                }
+            }
+        } else if c == separator {
+            parts.push(&s[part_start..i]);
+            part_start = i + c.len_utf8();
+        }
+    }
+    parts.push(&s[part_start..]);
+
+    parts
+}
+
+/// Split a rules string into its semicolon-separated clauses.
+///
+/// Semicolons inside of custom character classes don't end a clause. The returned clauses are
+/// trimmed, and empty clauses (such as the one following a trailing semicolon) are skipped.
+pub fn split_clauses(rules: &str) -> Vec<&str> {
+    split_outside_classes(rules, ';')
+        .into_iter()
+        .map(str::trim)
+        .filter(|clause| !clause.is_empty())
+        .collect()
+}
+
+/// Rewrite `rules` with canonical whitespace: a single space after each `:`, `,`, and `;`, and
+/// none anywhere else between tokens.
+///
+/// The parser accepts any amount of whitespace (including none) around `:`, `,`, and `;`, so
+/// this doesn't change what the rules mean. A trailing semicolon is kept if there was one.
+///
+/// ```
+/// use password_rules_checker::syntax::canonical_whitespace;
+///
+/// assert_eq!(
+///     canonical_whitespace(" required : upper ,lower;minlength:8 ;"),
+///     "required: upper, lower; minlength: 8;"
+/// );
+/// ```
+pub fn canonical_whitespace(rules: &str) -> String {
+    let clauses: Vec<String> = split_clauses(rules)
+        .into_iter()
+        .map(|clause| match clause.split_once(':') {
+            Some((name, values)) => {
+                let values: Vec<&str> = split_outside_classes(values, ',')
+                    .into_iter()
+                    .map(str::trim)
+                    .collect();
+                format!("{}: {}", name.trim(), values.join(", "))
+            }
+            None => clause.to_string(),
+        })
+        .collect();
+
+    let mut s = clauses.join("; ");
+    if rules.trim_end().ends_with(';') {
+        s.push(';');
+    }
+    s
+}
+
+/// Replace the semicolons ending empty clauses (such as the second semicolon in `minlength: 8;;`
+/// or a leading semicolon) with spaces.
+///
+/// The parser rejects empty clauses, but accepts whitespace between clauses, so this makes rules
+/// with stray semicolons parse. Every other character is left in place, so offsets into the
+/// returned string are the same as offsets into `rules`.
+pub fn blank_empty_clauses(rules: &str) -> String {
+    let mut blanked = String::with_capacity(rules.len());
+    let mut clause_is_empty = true;
+    let mut chars = rules.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ';' if clause_is_empty => {
+                blanked.push(' ');
+                continue;
+            }
+            ';' => clause_is_empty = true,
+            '[' => {
+                // Copy the custom class as is, so semicolons inside of it are left alone
+                blanked.push(c);
+                while let Some(c) = chars.next() {
+                    blanked.push(c);
+                    if c == ']' {
+                        if let Some(&']') = chars.peek() {
+                            blanked.push(']');
+                            chars.next();
+                        }
+                        break;
+                    }
+                }
+                clause_is_empty = false;
+                continue;
+            }
+            c if !c.is_whitespace() => clause_is_empty = false,
+            _ => {}
+        }
+        blanked.push(c);
+    }
+
+    blanked
+}
+
+/// Rewrite the custom classes in `rules` that end with a `-`, such as `[abc-]`, so the parser
+/// reads the `-` as a literal hyphen, as it does one at the start of a class.
+///
+/// The parser only accepts a `-` as the first character of a class, but a `-` at either end of a
+/// class can't be the middle of a range, so it's conventionally a literal. The `-` is moved to the
+/// front (`[-abc]`), or if there's one there already, replaced with a copy of another ASCII
+/// character in the class (`[-ab-]` becomes `[-abb]`, which the parser reads as the same
+/// characters). Every other character is left in place, so offsets into the returned string are
+/// the same as offsets into `rules`. Classes with a `-` anywhere else are left for the parser to
+/// reject.
+///
+/// ```
+/// use password_rules_checker::syntax::literal_trailing_hyphens;
+///
+/// assert_eq!(
+///     literal_trailing_hyphens("required: [abc-]; allowed: [-a-];"),
+///     "required: [-abc]; allowed: [-aa];"
+/// );
+/// ```
+pub fn literal_trailing_hyphens(rules: &str) -> String {
+    let mut rewritten = rules.to_string();
+
+    for (offset, body) in custom_class_bodies(rules) {
+        let without_hyphen = match body.strip_suffix('-') {
+            Some(without_hyphen) if !without_hyphen.is_empty() => without_hyphen,
+            _ => continue,
+        };
+        // Leave classes the parser will reject anyway alone, so its error points at the same
+        // character it would have
+        if without_hyphen.trim_start_matches('-').contains('-') {
+            continue;
+        }
+
+        let replacement = if let Some(rest) = without_hyphen.strip_prefix('-') {
+            // Keep the byte length the same, so only an ASCII character will do
+            match rest.chars().find(|&c| c.is_ascii() && c != '-') {
+                Some(c) => format!("{}{}", without_hyphen, c),
+                None => continue,
+            }
+        } else {
+            format!("-{}", without_hyphen)
+        };
+        rewritten.replace_range(offset..offset + body.len(), &replacement);
+    }
+
+    rewritten
+}
+
+/// The property name of a clause (such as `allowed` for `allowed: upper, lower`), lowercased
+pub fn clause_name(clause: &str) -> Option<String> {
+    let (name, _) = clause.split_once(':')?;
+    Some(name.trim().to_ascii_lowercase())
+}
+
+/// The properties in Apple's rules language that the parser understands
+pub const PROPERTIES: &[&str] = &[
+    "required",
+    "allowed",
+    "max-consecutive",
+    "minlength",
+    "maxlength",
+];
+
+/// The tokens for the built-in character classes
+pub const CLASS_TOKENS: &[&str] = &[
+    "upper",
+    "lower",
+    "digit",
+    "special",
+    "ascii-printable",
+    "unicode",
+];
+
+/// The names of the properties in `rules` that aren't in [`PROPERTIES`], in the order they first
+/// appear.
+///
+/// The parser rejects rules containing properties it doesn't understand with a generic syntax
+/// error, so this is a way to tell when a rule failed to parse because it uses a property that
+/// has been added to the language since the parser was written.
+pub fn unknown_properties(rules: &str) -> Vec<String> {
+    let mut unknown = Vec::new();
+    for name in split_clauses(rules).into_iter().filter_map(clause_name) {
+        if !PROPERTIES.contains(&name.as_str()) && !unknown.contains(&name) {
+            unknown.push(name);
+        }
+    }
+    unknown
+}
+
+/// Replace every `allowed` clause in `rules` with a single `allowed` clause containing `allowed`.
+///
+/// The new clause takes the place of the first `allowed` clause in the string; every other clause
+/// is preserved as written. If `allowed` is empty, the `allowed` clauses are removed entirely.
+pub fn replace_allowed(rules: &str, allowed: &[CharacterClass]) -> String {
+    let mut replaced = false;
+    let clauses: Vec<String> = split_clauses(rules)
+        .into_iter()
+        .filter_map(|clause| {
+            if clause_name(clause).as_deref() != Some("allowed") {
+                return Some(clause.to_string());
+            }
+
+            if replaced || allowed.is_empty() {
+                None
+            } else {
+                replaced = true;
+                Some(format!("allowed: {}", format_classes(allowed)))
+            }
+        })
+        .collect();
+
+    let mut s = clauses.join("; ");
+    if rules.trim_end().ends_with(';') {
+        s.push(';');
+    }
+    s
+}
+
+/// The classes in the `allowed` clauses of `rules`, in the order they're written.
+///
+/// The parser merges the `allowed` classes and puts them in a canonical order, so this parses each
+/// class on its own instead, as leniently as `check` does (see
+/// [`parse_password_rules_lenient`](crate::parse_password_rules_lenient)). Classes that fail to
+/// parse are skipped.
+///
+/// ```
+/// use password_rules_checker::syntax::written_allowed;
+/// use password_rules_parser::CharacterClass;
+///
+/// assert_eq!(
+///     written_allowed("allowed: lower, upper; minlength: 8; allowed: [xy]"),
+///     vec![
+///         CharacterClass::Lower,
+///         CharacterClass::Upper,
+///         CharacterClass::Custom(vec!['x', 'y'])
+///     ]
+/// );
+/// ```
+pub fn written_allowed(rules: &str) -> Vec<CharacterClass> {
+    let mut allowed = Vec::new();
+    for clause in split_clauses(rules) {
+        if clause_name(clause).as_deref() != Some("allowed") {
+            continue;
+        }
+
+        let (_, classes) = clause.split_once(':').unwrap_or_default();
+        for class in split_outside_classes(classes, ',') {
+            let class = format!("allowed: {};", class.trim());
+            if let Ok(parsed) = crate::parse_password_rules_lenient(&class, false) {
+                allowed.extend(parsed.allowed);
+            }
+        }
+    }
+    allowed
+}
+
+/// Expand the characters of a custom class body, turning ranges like `a-f` into the characters
+/// they span. `offset` is the byte offset of the body in `rules`.
+fn expand_class_body(
+    rules: &str,
+    body: &str,
+    offset: usize,
+) -> Result<Vec<char>, PasswordRulesError> {
+    let chars: Vec<(usize, char)> = body.char_indices().collect();
+    // The set is what makes large ranges fast; the `Vec` keeps the characters in the order
+    // they're written
+    let mut expanded: Vec<char> = Vec::new();
+    let mut seen: HashSet<char> = HashSet::new();
+    let mut push = |c: char| {
+        if seen.insert(c) {
+            expanded.push(c);
+        }
+    };
+
+    let mut i = 0;
+    while i < chars.len() {
+        let (index, start) = chars[i];
+        match (chars.get(i + 1), chars.get(i + 2)) {
+            // A `-` with a character on both sides is a range
+            (Some((_, '-')), Some(&(_, end))) => {
+                if start > end {
+                    return Err(invalid_range_error(rules, offset + index));
+                }
+                (start..=end).for_each(&mut push);
+                i += 3;
+            }
+            _ => {
+                push(start);
+                i += 1;
+            }
+        }
+    }
+
+    Ok(expanded)
+}
+
+/// Expand character ranges inside the custom classes of `rules`, so that `[a-f0-9]` becomes a
+/// class containing each of the characters `a` through `f` and `0` through `9`.
+///
+/// Apple's rules language, and therefore the parser, only has literal characters in custom
+/// classes (with `-` allowed as the first character), so ranges aren't understood unless this is
+/// run over a rules string before it's parsed. A `-` at the start or end of a class is left as a
+/// literal hyphen. Everything outside of custom classes is left untouched.
+///
+/// A range whose start comes after its end, such as `z-a`, is an error located at the start of
+/// the range, like the parser's errors (see [`crate::ErrorSpan`]).
+pub fn expand_ranges(rules: &str) -> Result<String, PasswordRulesError> {
+    let mut expanded = String::with_capacity(rules.len());
+    let mut end_of_last_class = 0;
+
+    for (offset, body) in custom_class_bodies(rules) {
+        // Copy everything between the classes, leaving out the opening bracket
+        expanded.push_str(&rules[end_of_last_class..offset - 1]);
+        let chars = expand_class_body(rules, body, offset)?;
+        expanded.push_str(&format_class(&CharacterClass::Custom(chars)));
+        end_of_last_class = offset + body.len() + 1;
+    }
+    expanded.push_str(&rules[end_of_last_class..]);
+
+    Ok(expanded)
+}
+
+/// The bodies of the custom classes in `rules` (what's between the brackets), each paired with
+/// its byte offset in `rules`. Unterminated classes are left out.
+fn custom_class_bodies(rules: &str) -> Vec<(usize, &str)> {
+    let mut bodies = Vec::new();
+    let mut rest = rules;
+
+    while let Some(open) = rest.find('[') {
+        let body_start = open + 1;
+
+        // A `]` immediately followed by another `]` is part of the class rather than the end
+        // of it
+        let close = match rest[body_start..].find(']') {
+            Some(close) if rest[body_start + close + 1..].starts_with(']') => {
+                body_start + close + 1
+            }
+            Some(close) => body_start + close,
+            // Leave unterminated classes for the parser to report
+            None => break,
+        };
+
+        let offset = rules.len() - rest.len() + body_start;
+        bodies.push((offset, &rest[body_start..close]));
+        rest = &rest[close + 1..];
+    }
+
+    bodies
+}
+
+/// The custom classes in `rules` that contain a character more than once, such as `[aabbc]`,
+/// each paired with the characters that are repeated, in the order they're first repeated.
+///
+/// The parser drops the repeats, so they don't change what the rules mean, but they suggest the
+/// class isn't what its author intended.
+///
+/// ```
+/// use password_rules_checker::syntax::duplicate_class_chars;
+///
+/// assert_eq!(
+///     duplicate_class_chars("required: [aabbc]; allowed: [xyz], [--a];"),
+///     vec![("[aabbc]", vec!['a', 'b']), ("[--a]", vec!['-'])]
+/// );
+/// ```
+pub fn duplicate_class_chars(rules: &str) -> Vec<(&str, Vec<char>)> {
+    custom_class_bodies(rules)
+        .into_iter()
+        .filter_map(|(offset, body)| {
+            let mut seen = Vec::new();
+            let mut duplicates = Vec::new();
+            for c in body.chars() {
+                if seen.contains(&c) {
+                    if !duplicates.contains(&c) {
+                        duplicates.push(c);
+                    }
+                } else {
+                    seen.push(c);
+                }
+            }
+
+            if duplicates.is_empty() {
+                None
+            } else {
+                // Include the brackets
+                Some((&rules[offset - 1..offset + body.len() + 1], duplicates))
+            }
+        })
+        .collect()
+}
+
+/// The characters that can follow a `\` in a custom class; see [`expand_escapes`]
+pub const ESCAPABLE_CHARS: &[char] = &[']', '-', '\\'];
+
+/// Resolve escaped characters inside the custom classes of `rules`, so that `\]`, `\-`, and
+/// `\\` can be written anywhere in a class to mean a literal `]`, `-`, and `\`.
+///
+/// Apple's rules language has no escapes: a `-` is only allowed as the first character of a
+/// class, a `]` only as the last (written `]]`), and a `\` is an ordinary character. Run this
+/// over a rules string before parsing it to use escapes instead; each class containing an escape
+/// is rewritten with its characters where the parser expects them. Every other character in a
+/// class is kept as a literal, so a `-` between two characters isn't a range, and classes
+/// without escapes are left untouched.
+///
+/// A `\` followed by any other character is an error, located like the parser's errors (see
+/// [`crate::ErrorSpan`]), that expects one of [`ESCAPABLE_CHARS`].
+///
+/// ```
+/// use password_rules_checker::syntax::expand_escapes;
+/// use password_rules_parser::{parse_password_rules, CharacterClass};
+///
+/// let expanded = expand_escapes(r"allowed: [a\]b\-];").unwrap();
+/// assert_eq!(expanded, "allowed: [-ab]];");
+/// assert_eq!(
+///     parse_password_rules(&expanded, false).unwrap().allowed,
+///     vec![CharacterClass::Custom(vec!['a', 'b', '-', ']'])]
+/// );
+///
+/// assert!(expand_escapes(r"allowed: [\q];").is_err());
+/// ```
+pub fn expand_escapes(rules: &str) -> Result<String, PasswordRulesError> {
+    let mut expanded = String::with_capacity(rules.len());
+    let mut rest = rules;
+
+    while let Some(open) = rest.find('[') {
+        let body_start = open + 1;
+        let mut chars = Vec::new();
+        let mut escaped = false;
+        let mut close = None;
+
+        let mut body = rest[body_start..].char_indices().peekable();
+        while let Some((i, c)) = body.next() {
+            match c {
+                '\\' => {
+                    let (escape_index, escape) = match body.next() {
+                        Some(escape) => escape,
+                        // Leave unterminated classes for the parser to report
+                        None => break,
+                    };
+                    if !ESCAPABLE_CHARS.contains(&escape) {
+                        let offset = rules.len() - rest.len() + body_start + escape_index;
+                        return Err(unknown_escape_error(rules, offset));
+                    }
+                    chars.push(escape);
+                    escaped = true;
+                }
+                // As in the parser, a `]` immediately followed by another `]` is part of the
+                // class rather than the end of it
+                ']' => match body.peek() {
+                    Some((_, ']')) => {
+                        body.next();
+                        chars.push(']');
+                        close = Some(body_start + i + 2);
+                        break;
+                    }
+                    _ => {
+                        close = Some(body_start + i + 1);
+                        break;
+                    }
+                },
+                c => chars.push(c),
+            }
+        }
+
+        let close = match close {
+            Some(close) => close,
+            None => break,
+        };
+        expanded.push_str(&rest[..open]);
+        if escaped {
+            expanded.push_str(&format_class(&CharacterClass::Custom(chars)));
+        } else {
+            expanded.push_str(&rest[open..close]);
+        }
+        rest = &rest[close..];
+    }
+    expanded.push_str(rest);
+
+    Ok(expanded)
+}
+
+/// An error at byte `index` of `rules` expecting each of `expected`
+fn error_at(
+    rules: &str,
+    index: usize,
+    expected: impl IntoIterator<Item = Expected>,
+) -> PasswordRulesError {
+    // Like the parser, locate the error relative to the trimmed input
+    let trimmed = rules.trim_start();
+    let index = index - (rules.len() - trimmed.len());
+    let (line, column) = location(trimmed, index);
+
+    PasswordRulesError {
+        expectations: expected
+            .into_iter()
+            .map(|expected| ExpectedAt {
+                index,
+                line,
+                column,
+                expected,
+            })
+            .collect(),
+    }
+}
+
+/// The error for an unknown escape whose escaped character is at byte `index` of `rules`.
+///
+/// The parser never expects a `\`, so expecting one (as one of [`ESCAPABLE_CHARS`]) is what
+/// marks the error as an escape error for [`crate::PasswordRulesErrorExt::kind`].
+fn unknown_escape_error(rules: &str, index: usize) -> PasswordRulesError {
+    error_at(
+        rules,
+        index,
+        ESCAPABLE_CHARS.iter().map(|&c| Expected::Char(c)),
+    )
+}
+
+/// What a descending range's error expects instead, such as `a-z` rather than `z-a`
+pub const ASCENDING_RANGE: &str = "a character range in ascending order";
+
+/// The error for a descending range that starts at byte `index` of `rules`
+fn invalid_range_error(rules: &str, index: usize) -> PasswordRulesError {
+    error_at(rules, index, Some(Expected::Tag(ASCENDING_RANGE)))
+}
+
+/// Expand both the escapes (see [`expand_escapes`]) and then the ranges (see [`expand_ranges`])
+/// inside the custom classes of `rules`. As with `expand_escapes`, a `-` in a class that contains
+/// an escape is a literal hyphen rather than part of a range.
+///
+/// Errors point into `rules` itself rather than the partly expanded string.
+///
+/// ```
+/// use password_rules_checker::syntax::expand_classes;
+///
+/// assert_eq!(
+///     expand_classes(r"allowed: [a-c], [\-];").unwrap(),
+///     "allowed: [abc], [-];"
+/// );
+/// assert_eq!(
+///     expand_classes(r"allowed: [\]], [c-a];").unwrap_err().expectations[0].index,
+///     16
+/// );
+/// ```
+pub fn expand_classes(rules: &str) -> Result<String, PasswordRulesError> {
+    let escaped = expand_escapes(rules)?;
+    expand_ranges(&escaped).map_err(|mut e| {
+        locate_expanded_error(&mut e, rules, &escaped);
+        e
+    })
+}
+
+/// The length of the custom class at the start of `s`, brackets included, or `None` if it's
+/// unterminated. `escapes` is whether a `\` escapes the character after it.
+fn class_len(s: &str, escapes: bool) -> Option<usize> {
+    let mut chars = s.char_indices().skip(1).peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if escapes => {
+                chars.next()?;
+            }
+            ']' => {
+                return match chars.peek() {
+                    Some(&(_, ']')) => Some(i + 2),
+                    _ => Some(i + 1),
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Move the locations in `e`, an error from parsing `expanded`, to point into `original`, where
+/// `expanded` is `original` with its custom classes rewritten by [`expand_escapes`] or
+/// [`expand_ranges`] (or both, as [`expand_classes`] does).
+///
+/// Everything outside of the custom classes is the same in both strings, so locations there move
+/// by however much the classes before them grew or shrank. A location inside a class that was
+/// rewritten moves to the start of the class. Lines and columns are recounted, in characters.
+pub fn locate_expanded_error(e: &mut PasswordRulesError, original: &str, expanded: &str) {
+    // The parser's locations are relative to the trimmed input, and rewriting the classes never
+    // changes where the input starts
+    let original = original.trim_start();
+    let expanded = expanded.trim_start();
+
+    for expected in e.expectations.iter_mut() {
+        expected.index = original_index(original, expanded, expected.index);
+        let (line, column) = location(original, expected.index);
+        expected.line = line;
+        expected.column = column;
+    }
+}
+
+/// The byte offset in `original` of byte `index` of `expanded`; see [`locate_expanded_error`]
+fn original_index(original: &str, expanded: &str, index: usize) -> usize {
+    let (mut o, mut e) = (0, 0);
+    while e < index {
+        let c = match expanded[e..].chars().next() {
+            Some(c) => c,
+            None => break,
+        };
+        if c != '[' {
+            o += c.len_utf8();
+            e += c.len_utf8();
+            continue;
+        }
+
+        // Unterminated classes are never rewritten, so the rest of the strings are the same
+        let (o_len, e_len) = match (
+            class_len(&original[o..], true),
+            class_len(&expanded[e..], false),
+        ) {
+            (Some(o_len), Some(e_len)) => (o_len, e_len),
+            _ => break,
+        };
+        if index < e + e_len {
+            return if o_len == e_len { o + index - e } else { o };
+        }
+        o += o_len;
+        e += e_len;
+    }
+
+    (o + index - e).min(original.len())
+}
+
+/// Extension methods for turning `PasswordRules` back into a rules string
+pub trait ToRulesString {
+    /// Render the rules in Apple's rules language.
+    ///
+    /// Clauses are emitted in a canonical order (`minlength`, `maxlength`, `required`, `allowed`,
+    /// `max-consecutive`) and each one is terminated with a semicolon, as in Apple's quirks file.
+    /// Properties that weren't set are omitted.
+    ///
+    /// ```
+    /// use password_rules_checker::syntax::ToRulesString;
+    /// use password_rules_parser::parse_password_rules;
+    ///
+    /// let rules = parse_password_rules("allowed: lower, [-]; minlength: 8;", false).unwrap();
+    ///
+    /// assert_eq!(rules.to_rules_string(), "minlength: 8; allowed: lower, [-];");
+    /// ```
+    fn to_rules_string(&self) -> String;
+}
+
+impl ToRulesString for PasswordRules {
+    fn to_rules_string(&self) -> String {
+        let mut clauses = Vec::new();
+        if let Some(min_length) = self.min_length {
+            clauses.push(format!("minlength: {};", min_length));
+        }
+        if let Some(max_length) = self.max_length {
+            clauses.push(format!("maxlength: {};", max_length));
+        }
+        for classes in self.required.iter() {
+            clauses.push(format!("required: {};", format_classes(classes)));
+        }
+        if !self.allowed.is_empty() {
+            clauses.push(format!("allowed: {};", format_classes(&self.allowed)));
+        }
+        if let Some(max_consecutive) = self.max_consecutive {
+            clauses.push(format!("max-consecutive: {};", max_consecutive));
+        }
+
+        clauses.join(" ")
+    }
+}
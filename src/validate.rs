@@ -0,0 +1,217 @@
+//! Checking candidate passwords against parsed password rules
+
+use crate::class::CharacterClassExt;
+use crate::rules::PasswordRulesExt;
+use crate::syntax;
+use password_rules_parser::{CharacterClass, PasswordRules};
+use std::fmt::{self, Display, Formatter};
+
+/// A single way in which a password fails to satisfy a set of rules
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// The password has fewer characters than `min_length`
+    TooShort { min_length: u32, length: usize },
+    /// The password has more characters than `max_length`
+    TooLong { max_length: u32, length: usize },
+    /// The password doesn't contain a character from any of the classes in a `required` group
+    MissingRequired(Vec<CharacterClass>),
+    /// The password contains a character that isn't in any `allowed` or `required` class
+    DisallowedCharacter(char),
+    /// The password repeats a character more than `max_consecutive` times in a row
+    TooManyConsecutive {
+        character: char,
+        count: usize,
+        max_consecutive: u32,
+    },
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::TooShort { min_length, length } => write!(
+                f,
+                "must be at least {} characters long, but is {}",
+                min_length, length
+            ),
+            Violation::TooLong { max_length, length } => write!(
+                f,
+                "must be at most {} characters long, but is {}",
+                max_length, length
+            ),
+            Violation::MissingRequired(classes) => write!(
+                f,
+                "must contain a character from: {}",
+                syntax::format_classes(classes)
+            ),
+            Violation::DisallowedCharacter(c) => {
+                write!(f, "contains a disallowed character {:?}", c)
+            }
+            Violation::TooManyConsecutive {
+                character,
+                count,
+                max_consecutive,
+            } => write!(
+                f,
+                "repeats {:?} {} times in a row, but at most {} are allowed",
+                character, count, max_consecutive
+            ),
+        }
+    }
+}
+
+/// Every way in which a password fails to satisfy a set of rules
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    pub violations: Vec<Violation>,
+}
+
+impl ValidationReport {
+    /// Whether the password satisfied every rule
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+impl Display for ValidationReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.is_valid() {
+            return writeln!(f, "The password satisfies every rule");
+        }
+
+        writeln!(f, "The password:")?;
+        for violation in self.violations.iter() {
+            writeln!(f, "  * {}", violation)?;
+        }
+        Ok(())
+    }
+}
+
+/// Extension methods for checking passwords against `PasswordRules`
+pub trait Validate {
+    /// Check `password` against every one of the rules, reporting each rule it violates.
+    ///
+    /// A password is valid if:
+    ///
+    /// * Its length in characters is within `min_length` and `max_length` (either of which may be
+    ///   absent, leaving that end unbounded)
+    /// * It contains at least one character from each group of `required` classes
+    /// * Every character is in one of the `allowed` or `required` classes (`unicode` contains
+    ///   every character, including ones outside of ASCII such as emoji)
+    /// * No character is repeated more than `max_consecutive` times in a row
+    ///
+    /// If there are no `allowed` or `required` classes at all, any `ascii-printable` character is
+    /// allowed, matching the specification's default.
+    fn check(&self, password: &str) -> ValidationReport;
+
+    /// Whether `password` satisfies every one of the rules; see [`Validate::check`]
+    fn validate(&self, password: &str) -> bool {
+        self.check(password).is_valid()
+    }
+
+    /// Whether a password `len` characters long satisfies `min_length` and `max_length`, without
+    /// looking at the password itself.
+    ///
+    /// Both bounds are inclusive, and a bound that isn't set doesn't limit the length. The length
+    /// of a password is the number of characters (`char`s) in it, not its length in bytes, so
+    /// pass `password.chars().count()`. This is a cheap way to reject passwords that can't be
+    /// valid before checking them in full.
+    ///
+    /// ```
+    /// use password_rules_checker::Validate;
+    /// use password_rules_parser::parse_password_rules;
+    ///
+    /// let rules = parse_password_rules("minlength: 8; maxlength: 16;", true).unwrap();
+    ///
+    /// assert!(!rules.length_ok(7));
+    /// assert!(rules.length_ok(8));
+    /// assert!(rules.length_ok(16));
+    /// assert!(!rules.length_ok(17));
+    /// ```
+    fn length_ok(&self, len: usize) -> bool;
+}
+
+impl Validate for PasswordRules {
+    fn length_ok(&self, len: usize) -> bool {
+        let len = len as u64;
+        self.min_length
+            .is_none_or(|min_length| len >= min_length.into())
+            && self
+                .max_length
+                .is_none_or(|max_length| len <= max_length.into())
+    }
+
+    fn check(&self, password: &str) -> ValidationReport {
+        let mut violations = Vec::new();
+
+        let length = password.chars().count();
+        if let Some(min_length) = self.min_length {
+            if (length as u64) < min_length.into() {
+                violations.push(Violation::TooShort { min_length, length });
+            }
+        }
+        if let Some(max_length) = self.max_length {
+            if (length as u64) > max_length.into() {
+                violations.push(Violation::TooLong { max_length, length });
+            }
+        }
+
+        for classes in self.required.iter() {
+            if !password
+                .chars()
+                .any(|c| classes.iter().any(|class| class.contains(c)))
+            {
+                violations.push(Violation::MissingRequired(classes.clone()));
+            }
+        }
+
+        // Characters from the required classes are implicitly allowed
+        let mut permitted: Vec<&CharacterClass> =
+            self.allowed.iter().chain(self.required_classes()).collect();
+        if permitted.is_empty() {
+            permitted.push(&CharacterClass::AsciiPrintable);
+        }
+        let mut disallowed = Vec::new();
+        for c in password.chars() {
+            if !permitted.iter().any(|class| class.contains(c)) && !disallowed.contains(&c) {
+                disallowed.push(c);
+            }
+        }
+        violations.extend(disallowed.into_iter().map(Violation::DisallowedCharacter));
+
+        if let Some(max_consecutive) = self.max_consecutive {
+            if let Some((character, count)) = longest_run(password) {
+                if count > max_consecutive as usize {
+                    violations.push(Violation::TooManyConsecutive {
+                        character,
+                        count,
+                        max_consecutive,
+                    });
+                }
+            }
+        }
+
+        ValidationReport { violations }
+    }
+}
+
+/// The character with the longest run of repetitions in `s`, along with the length of that run
+pub(crate) fn longest_run(s: &str) -> Option<(char, usize)> {
+    let mut longest: Option<(char, usize)> = None;
+    let mut current = 0;
+    let mut previous = None;
+
+    for c in s.chars() {
+        if Some(c) == previous {
+            current += 1;
+        } else {
+            current = 1;
+            previous = Some(c);
+        }
+
+        if longest.is_none_or(|(_, count)| current > count) {
+            longest = Some((c, current));
+        }
+    }
+
+    longest
+}
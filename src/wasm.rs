@@ -0,0 +1,46 @@
+//! WebAssembly bindings for parsing rules from JavaScript, enabled by the `wasm` feature.
+//!
+//! The crate isn't built as a `cdylib` by default, so build the bindings with:
+//!
+//! ```text
+//! cargo rustc --lib --release --crate-type cdylib --target wasm32-unknown-unknown --features wasm
+//! ```
+//!
+//! and then run `wasm-bindgen` over the result.
+
+use crate::error::{ErrorSpan, Span};
+use crate::serde_rules::SerdeRules;
+use password_rules_parser::parse_password_rules;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+/// The result of parsing rules, as returned to JavaScript
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ParseResult {
+    Rules(SerdeRules),
+    Error {
+        message: String,
+        /// The location of the error, if it has one
+        span: Option<Span>,
+    },
+}
+
+/// Parse `rules`, returning JSON describing either the parsed rules or the error.
+///
+/// On success this returns `{"rules": {...}}`, where the rules are in the format used by
+/// [`SerdeRules`]. On failure it returns `{"error": {"message": "...", "span": {...}}}`, where the
+/// span has the byte offsets and the line and column of the offending token.
+#[wasm_bindgen(js_name = parsePasswordRules)]
+pub fn parse_password_rules_json(rules: &str, supply_default: bool) -> String {
+    let result = match parse_password_rules(rules, supply_default) {
+        Ok(parsed) => ParseResult::Rules(SerdeRules(parsed)),
+        Err(e) => ParseResult::Error {
+            message: e.to_string(),
+            span: e.span(rules),
+        },
+    };
+
+    // Serializing these types to JSON can't fail
+    serde_json::to_string(&result).unwrap()
+}
@@ -0,0 +1,35 @@
+#![cfg(feature = "async")]
+
+use password_rules_checker::quirks::{load_rules_map, load_rules_map_async, LoadError};
+use std::fs;
+
+#[tokio::test]
+async fn async_loading_matches_sync_loading() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("password-rules-checker-async-load.json");
+    fs::write(
+        &path,
+        r#"{"b.com": {"password-rules": "minlength: 8;"}, "a.com": {"password-rules": "required: upper;"}}"#,
+    )
+    .unwrap();
+
+    let loaded = load_rules_map_async(&path).await.unwrap();
+    let expected = load_rules_map(&path).unwrap();
+
+    let sites: Vec<&String> = loaded.keys().collect();
+    assert_eq!(sites, expected.keys().collect::<Vec<_>>());
+    assert_eq!(loaded["b.com"].password_rules, "minlength: 8;");
+}
+
+#[tokio::test]
+async fn async_loading_reports_the_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("password-rules-checker-missing.json");
+
+    let e = load_rules_map_async(&path).await.unwrap_err();
+
+    assert!(matches!(e, LoadError::Read { .. }));
+    assert!(e
+        .to_string()
+        .contains("password-rules-checker-missing.json"));
+}
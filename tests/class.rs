@@ -0,0 +1,64 @@
+use password_rules_checker::CharacterClassExt;
+use password_rules_parser::CharacterClass;
+
+#[test]
+fn ascii_printable_has_95_characters() {
+    let chars = CharacterClass::AsciiPrintable.chars();
+
+    assert_eq!(chars.len(), 95);
+    assert!(chars.contains(&' '));
+    assert_eq!(chars, (' '..='~').collect::<Vec<_>>());
+}
+
+#[test]
+fn builtin_members_agree_with_contains() {
+    let classes = [
+        CharacterClass::Upper,
+        CharacterClass::Lower,
+        CharacterClass::Digit,
+        CharacterClass::Special,
+        CharacterClass::AsciiPrintable,
+    ];
+
+    for class in classes.iter() {
+        let members = class.members().unwrap();
+        for c in (0..=0x7f_u8).map(char::from) {
+            assert_eq!(
+                class.contains(c),
+                members.contains(&c),
+                "{:?} and {:?} disagree",
+                class,
+                c
+            );
+        }
+    }
+}
+
+#[test]
+fn classes_sort_into_a_canonical_order() {
+    let mut classes = vec![
+        CharacterClass::Custom(vec!['b']),
+        CharacterClass::Unicode,
+        CharacterClass::Custom(vec!['a', 'c']),
+        CharacterClass::Special,
+        CharacterClass::AsciiPrintable,
+        CharacterClass::Digit,
+        CharacterClass::Lower,
+        CharacterClass::Upper,
+    ];
+    classes.sort();
+
+    assert_eq!(
+        classes,
+        vec![
+            CharacterClass::Upper,
+            CharacterClass::Lower,
+            CharacterClass::Digit,
+            CharacterClass::Special,
+            CharacterClass::AsciiPrintable,
+            CharacterClass::Unicode,
+            CharacterClass::Custom(vec!['a', 'c']),
+            CharacterClass::Custom(vec!['b']),
+        ]
+    );
+}
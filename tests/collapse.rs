@@ -0,0 +1,105 @@
+use password_rules_checker::{Lint, PasswordRulesExt};
+use password_rules_parser::{parse_password_rules, CharacterClass, PasswordRules};
+
+fn collapse(allowed: Vec<CharacterClass>) -> Vec<CharacterClass> {
+    PasswordRules {
+        allowed,
+        ..PasswordRules::default()
+    }
+    .collapse_allowed()
+    .allowed
+}
+
+#[test]
+fn ascii_subclasses_collapse_into_ascii_printable() {
+    assert_eq!(
+        collapse(vec![
+            CharacterClass::Special,
+            CharacterClass::Digit,
+            CharacterClass::Lower,
+            CharacterClass::Upper,
+        ]),
+        vec![CharacterClass::AsciiPrintable]
+    );
+}
+
+#[test]
+fn classes_only_collapse_into_an_equivalent_class() {
+    assert_eq!(
+        collapse(vec![
+            CharacterClass::Upper,
+            CharacterClass::Lower,
+            CharacterClass::Digit,
+        ]),
+        vec![
+            CharacterClass::Upper,
+            CharacterClass::Lower,
+            CharacterClass::Digit,
+        ]
+    );
+}
+
+#[test]
+fn contained_classes_are_dropped() {
+    assert_eq!(
+        collapse(vec![CharacterClass::Digit, CharacterClass::Unicode]),
+        vec![CharacterClass::Unicode]
+    );
+    assert_eq!(
+        collapse(vec![
+            CharacterClass::AsciiPrintable,
+            CharacterClass::Upper,
+            CharacterClass::Custom(vec!['é', '-']),
+        ]),
+        vec![
+            CharacterClass::AsciiPrintable,
+            CharacterClass::Custom(vec!['é'])
+        ]
+    );
+}
+
+#[test]
+fn custom_classes_are_split_into_named_classes() {
+    let digits: Vec<char> = ('0'..='9').chain(Some('-')).collect();
+
+    assert_eq!(
+        collapse(vec![CharacterClass::Custom(digits)]),
+        vec![CharacterClass::Digit, CharacterClass::Custom(vec!['-'])]
+    );
+}
+
+fn shortened_allowed(rules: &str) -> Option<Vec<CharacterClass>> {
+    parse_password_rules(rules, false)
+        .unwrap()
+        .shortened_allowed()
+}
+
+#[test]
+fn shortening_suggests_ascii_printable_when_every_subclass_is_permitted() {
+    assert_eq!(
+        shortened_allowed("required: digit; required: special; allowed: upper, lower, digit;"),
+        Some(vec![CharacterClass::AsciiPrintable])
+    );
+}
+
+#[test]
+fn shortening_lists_the_classes_when_a_subclass_is_missing() {
+    assert_eq!(
+        shortened_allowed("required: digit; allowed: upper, lower, digit;"),
+        Some(vec![CharacterClass::Upper, CharacterClass::Lower])
+    );
+}
+
+#[test]
+fn shortening_never_adds_an_allowed_property() {
+    assert_eq!(
+        shortened_allowed(
+            "required: upper; required: lower; required: digit; required: special; allowed: upper;"
+        ),
+        Some(vec![])
+    );
+    assert_eq!(
+        shortened_allowed("required: digit; required: special; allowed: upper, lower;"),
+        None
+    );
+}
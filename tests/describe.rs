@@ -0,0 +1,60 @@
+use password_rules_checker::Describe;
+use password_rules_parser::{parse_password_rules, PasswordRules};
+
+/// Describe `rules`, which are parsed without supplying a default `allowed` class
+fn describe(rules: &str) -> String {
+    parse_password_rules(rules, false).unwrap().describe()
+}
+
+#[test]
+fn describes_every_property() {
+    assert_eq!(
+        describe(
+            "minlength: 8; maxlength: 20; required: upper; required: digit; \
+            allowed: ascii-printable; max-consecutive: 3;"
+        ),
+        "Passwords must be 8–20 characters, contain at least one uppercase letter and one digit, \
+        allow ASCII-printable characters, and forbid more than 3 repeated characters in a row."
+    );
+}
+
+#[test]
+fn describes_lengths() {
+    assert_eq!(
+        describe("minlength: 12;"),
+        "Passwords must be at least 12 characters (with no maximum length)."
+    );
+    assert_eq!(
+        describe("maxlength: 16;"),
+        "Passwords must be at most 16 characters."
+    );
+    assert_eq!(
+        describe("minlength: 6; maxlength: 6;"),
+        "Passwords must be exactly 6 characters."
+    );
+}
+
+#[test]
+fn pluralizes_counts_of_one() {
+    assert_eq!(
+        describe("minlength: 1; maxlength: 1; max-consecutive: 1;"),
+        "Passwords must be exactly 1 character and forbid more than 1 repeated character in a row."
+    );
+}
+
+#[test]
+fn describes_groups_and_custom_classes() {
+    assert_eq!(
+        describe("required: upper, digit; allowed: lower, [-_];"),
+        "Passwords must contain at least one uppercase letter or digit and allow lowercase \
+        letters and the characters `[-_]`."
+    );
+}
+
+#[test]
+fn describes_unrestricted_rules() {
+    assert_eq!(
+        PasswordRules::default().describe(),
+        "Passwords aren't restricted in any way."
+    );
+}
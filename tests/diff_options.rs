@@ -0,0 +1,69 @@
+use password_rules_checker::diff::{diff_rules, diff_rules_with};
+use password_rules_checker::syntax;
+use password_rules_checker::DiffOptions;
+use password_rules_parser::{parse_password_rules, CharacterClass};
+
+const SHORTENED: (&str, &str) = (
+    "required: upper; allowed: upper, lower;",
+    "required: upper; allowed: lower;",
+);
+
+#[test]
+fn shortened_allowed_is_equivalent_by_default() {
+    let rules = parse_password_rules(SHORTENED.0, true).unwrap();
+    let other_rules = parse_password_rules(SHORTENED.1, true).unwrap();
+
+    assert!(diff_rules(&rules, &other_rules).is_empty());
+}
+
+#[test]
+fn strict_allowed_compares_allowed_as_written() {
+    let rules = parse_password_rules(SHORTENED.0, true).unwrap();
+    let other_rules = parse_password_rules(SHORTENED.1, true).unwrap();
+    let options = DiffOptions {
+        strict_allowed: true,
+        ..DiffOptions::default()
+    };
+
+    let diffs = diff_rules_with(&rules, &other_rules, &options);
+    assert_eq!(diffs.len(), 1);
+    assert_eq!(diffs[0].field, "allowed");
+    assert_eq!(diffs[0].left, "upper, lower");
+    assert_eq!(diffs[0].right, "lower");
+
+    // Re-ordering the classes still isn't a difference
+    let reordered = parse_password_rules("required: upper; allowed: lower, upper;", true).unwrap();
+    assert!(diff_rules_with(&rules, &reordered, &options).is_empty());
+}
+
+#[test]
+fn ordered_allowed_compares_allowed_as_a_sequence() {
+    let written = ["allowed: lower, upper;", "allowed: upper, lower;"];
+    let [rules, other_rules] = written.map(|written| {
+        let mut rules = parse_password_rules(written, true).unwrap();
+        rules.allowed = syntax::written_allowed(written);
+        rules
+    });
+    assert!(diff_rules(&rules, &other_rules).is_empty());
+
+    let options = DiffOptions {
+        ordered_allowed: true,
+        ..DiffOptions::default()
+    };
+    let diffs = diff_rules_with(&rules, &other_rules, &options);
+    assert_eq!(diffs.len(), 1);
+    assert_eq!(diffs[0].left, "lower, upper");
+    assert_eq!(diffs[0].right, "upper, lower");
+}
+
+#[test]
+fn written_allowed_reads_classes_leniently() {
+    // A trailing hyphen is only accepted by the lenient parse
+    assert_eq!(
+        syntax::written_allowed("allowed: digit, [abc-];"),
+        vec![
+            CharacterClass::Digit,
+            CharacterClass::Custom(vec!['a', 'b', 'c', '-'])
+        ]
+    );
+}
@@ -0,0 +1,27 @@
+use password_rules_checker::lint::duplicate_class_char_findings;
+use password_rules_checker::syntax::duplicate_class_chars;
+use password_rules_checker::Severity;
+
+#[test]
+fn classes_without_repeats_have_no_findings() {
+    for rules in [
+        "required: upper; allowed: [abc];",
+        "allowed: [-().];",
+        "allowed: [a]; required: [a];",
+    ] {
+        assert!(duplicate_class_chars(rules).is_empty(), "{}", rules);
+    }
+}
+
+#[test]
+fn repeats_are_reported_at_info() {
+    let findings = duplicate_class_char_findings("minlength: 8; allowed: lower, [aabbc];");
+
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].lint, "duplicate-class-character");
+    assert_eq!(findings[0].severity, Severity::Info);
+    assert_eq!(
+        findings[0].message,
+        "the custom class `[aabbc]` contains `a`, `b` more than once"
+    );
+}
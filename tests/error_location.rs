@@ -0,0 +1,67 @@
+use password_rules_checker::{parse_password_rules_recover, ErrorSpan};
+use password_rules_parser::parse_password_rules;
+
+/// The line and column of the first expectation of the error from parsing `rules`, after
+/// correcting them with `locate_in`
+fn located(rules: &str) -> (u32, u32) {
+    let mut e = parse_password_rules(rules, false).unwrap_err();
+    e.locate_in(rules);
+    (e.expectations[0].line, e.expectations[0].column)
+}
+
+#[test]
+fn columns_count_characters() {
+    // `foo` starts at the 15th character, but the 16th byte
+    assert_eq!(located("allowed: [é]; foo: 1"), (1, 15));
+    assert_eq!(located("allowed: [🔑]; foo: 1"), (1, 15));
+}
+
+#[test]
+fn columns_account_for_leading_whitespace() {
+    assert_eq!(located("   minlength: 8; foo"), (1, 18));
+}
+
+#[test]
+fn columns_reset_on_each_line() {
+    assert_eq!(located("allowed: [ééé];\nrequired: [é]; bar"), (2, 16));
+}
+
+#[test]
+fn errors_at_the_end_of_input() {
+    // The error is just past the last character
+    assert_eq!(located("allowed: [é"), (1, 12));
+}
+
+#[test]
+fn pretty_errors_point_at_the_offending_character() {
+    let rules = "allowed: [é]; foo: 1";
+    let mut e = parse_password_rules(rules, false).unwrap_err();
+    e.locate_in(rules);
+    let pretty = e.to_string_pretty(rules).unwrap();
+
+    let source_line = pretty.lines().find(|line| line.contains("foo")).unwrap();
+    let caret_line = pretty.lines().find(|line| line.contains('^')).unwrap();
+    assert_eq!(
+        source_line.chars().position(|c| c == 'f'),
+        caret_line.chars().position(|c| c == '^')
+    );
+}
+
+#[test]
+fn recovered_errors_count_characters() {
+    let (_, errors) = parse_password_rules_recover("allowed: [é]; foo: 1; minlength: 8", false);
+
+    assert_eq!(errors[0].expectations[0].column, 15);
+}
+
+#[test]
+fn spans_count_characters() {
+    let rules = "allowed: [é]; foo: 1";
+    let span = parse_password_rules(rules, false)
+        .unwrap_err()
+        .span(rules)
+        .unwrap();
+
+    assert_eq!(&rules[span.start..span.end], "foo:");
+    assert_eq!((span.line, span.column), (1, 15));
+}
@@ -0,0 +1,82 @@
+use password_rules_checker::syntax::expand_escapes;
+use password_rules_checker::{ErrorKind, ErrorSpan, PasswordRulesErrorExt};
+use password_rules_parser::{parse_password_rules, CharacterClass};
+
+fn allowed(rules: &str) -> Vec<CharacterClass> {
+    let expanded = expand_escapes(rules).unwrap();
+    parse_password_rules(&expanded, false)
+        .unwrap_or_else(|e| panic!("{:?} (from {:?}) failed to parse: {}", expanded, rules, e))
+        .allowed
+}
+
+#[test]
+fn escaped_brackets_are_literal() {
+    assert_eq!(
+        allowed(r"allowed: [\]ab];"),
+        vec![CharacterClass::Custom(vec!['a', 'b', ']'])]
+    );
+    assert_eq!(
+        allowed(r"allowed: [a\]];"),
+        vec![CharacterClass::Custom(vec!['a', ']'])]
+    );
+}
+
+#[test]
+fn escaped_hyphens_are_literal() {
+    assert_eq!(
+        allowed(r"allowed: [a\-z];"),
+        vec![CharacterClass::Custom(vec!['a', 'z', '-'])]
+    );
+    assert_eq!(
+        allowed(r"allowed: [ab\-];"),
+        vec![CharacterClass::Custom(vec!['a', 'b', '-'])]
+    );
+}
+
+#[test]
+fn escaped_backslashes_are_literal() {
+    assert_eq!(
+        allowed(r"allowed: [a\\];"),
+        vec![CharacterClass::Custom(vec!['a', '\\'])]
+    );
+    assert_eq!(
+        allowed(r"allowed: [\\\]];"),
+        vec![CharacterClass::Custom(vec!['\\', ']'])]
+    );
+}
+
+#[test]
+fn rules_without_escapes_are_unchanged() {
+    for rules in [
+        "minlength: 8; required: upper; allowed: [_!]];",
+        "allowed: [-a], lower;",
+        "allowed: [a",
+        "required: digit",
+    ] {
+        assert_eq!(expand_escapes(rules).unwrap(), rules);
+    }
+}
+
+#[test]
+fn unknown_escapes_are_errors() {
+    let rules = r"  required: upper; allowed: [ab\q];";
+    let e = expand_escapes(rules).unwrap_err();
+
+    // The error points at the escaped character, relative to the trimmed input like the parser's
+    // errors
+    assert_eq!(e.expectations.len(), 3);
+    assert_eq!(e.expectations[0].index, 30);
+    assert_eq!((e.expectations[0].line, e.expectations[0].column), (1, 31));
+    assert_eq!(e.span(rules).unwrap().start, 32);
+    assert!(e.to_string().contains("']'"), "{}", e);
+}
+
+#[test]
+fn unknown_escapes_have_their_own_kind() {
+    let e = expand_escapes(r"allowed: [\q];").unwrap_err();
+    assert_eq!(e.kind(), ErrorKind::InvalidEscape);
+
+    // A class that's really unterminated is still reported as one
+    let e = parse_password_rules("allowed: [ab", false).unwrap_err();
+    assert_eq!(e.kind(), ErrorKind::UnterminatedClass);
+}
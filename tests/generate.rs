@@ -0,0 +1,416 @@
+use password_rules_checker::{
+    CharacterClassExt, Generate, GenerateError, GenerateMode, GenerateOptions,
+    PasswordRulesBuilder, ToRulesString, Validate, MAX_GENERATED_LENGTH,
+};
+use password_rules_parser::{parse_password_rules, CharacterClass, PasswordRules};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+/// Generate many passwords for `rules`, asserting that every one of them satisfies the rules
+fn assert_generated_passwords_valid(rules: &str) {
+    let rules = parse_password_rules(rules, true).unwrap();
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for _ in 0..1000 {
+        let password = rules.generate(&mut rng).unwrap();
+        assert!(
+            rules.validate(&password),
+            "{:?} doesn't satisfy the rules: {}",
+            password,
+            rules.check(&password)
+        );
+    }
+}
+
+#[test]
+fn max_consecutive_one_never_repeats_adjacent_characters() {
+    let rules =
+        parse_password_rules("minlength: 30; max-consecutive: 1; allowed: [ab];", true).unwrap();
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for _ in 0..1000 {
+        let password = rules.generate(&mut rng).unwrap();
+        let chars: Vec<char> = password.chars().collect();
+        assert!(
+            chars.windows(2).all(|pair| pair[0] != pair[1]),
+            "{:?} repeats a character",
+            password
+        );
+    }
+}
+
+#[test]
+fn max_consecutive_is_respected_with_small_alphabets() {
+    assert_generated_passwords_valid("minlength: 20; max-consecutive: 1; allowed: [ab];");
+    assert_generated_passwords_valid(
+        "minlength: 20; max-consecutive: 2; required: [x]; allowed: [y];",
+    );
+    assert_generated_passwords_valid("minlength: 20; max-consecutive: 3; allowed: digit;");
+}
+
+#[test]
+fn max_consecutive_is_respected_with_required_classes() {
+    assert_generated_passwords_valid(
+        "minlength: 12; maxlength: 16; max-consecutive: 1; required: upper; required: digit; \
+         required: [-]; allowed: lower;",
+    );
+}
+
+#[test]
+fn unsatisfiable_max_consecutive_is_an_error() {
+    let rules =
+        parse_password_rules("minlength: 2; max-consecutive: 1; allowed: [a];", true).unwrap();
+    assert!(rules.generate(&mut StdRng::seed_from_u64(0)).is_err());
+}
+
+#[test]
+fn unicode_passwords_are_valid() {
+    assert_generated_passwords_valid("minlength: 12; required: unicode; allowed: digit;");
+}
+
+#[test]
+fn unicode_pool_can_be_overridden() {
+    let rules = parse_password_rules("minlength: 16; allowed: unicode;", true).unwrap();
+    let options = GenerateOptions {
+        unicode_pool: vec!['ä', 'ö', 'ü'],
+        ..GenerateOptions::default()
+    };
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for _ in 0..100 {
+        let password = rules.generate_with(&options, &mut rng).unwrap();
+        assert!(password.chars().all(|c| options.unicode_pool.contains(&c)));
+    }
+}
+
+#[test]
+fn failing_passwords_violate_the_rules() {
+    let corpus = [
+        "minlength: 8; maxlength: 20; required: upper; required: digit;",
+        "allowed: lower;",
+        "minlength: 4; max-consecutive: 2; allowed: unicode;",
+        "required: [-]; allowed: ascii-printable;",
+    ];
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for rules in corpus.iter() {
+        let rules = parse_password_rules(rules, true).unwrap();
+        for _ in 0..100 {
+            let (password, violation) = rules.generate_failing(&mut rng).unwrap();
+            assert!(rules.check(&password).violations.contains(&violation));
+        }
+    }
+}
+
+#[test]
+fn unconstrained_rules_cant_be_broken() {
+    let rules = parse_password_rules("allowed: unicode;", true).unwrap();
+
+    assert_eq!(rules.generate_failing(&mut StdRng::seed_from_u64(0)), None);
+}
+
+/// A random set of rules built from a handful of lengths and classes, which may be unsatisfiable
+fn random_rules(rng: &mut StdRng) -> PasswordRules {
+    let classes = [
+        CharacterClass::Upper,
+        CharacterClass::Lower,
+        CharacterClass::Digit,
+        CharacterClass::Special,
+        CharacterClass::AsciiPrintable,
+        CharacterClass::Unicode,
+        CharacterClass::Custom(vec!['-', ']']),
+        CharacterClass::Custom(vec!['a', 'b', 'c']),
+        CharacterClass::Custom(vec!['x']),
+    ];
+    let random_classes = |rng: &mut StdRng| {
+        let count = rng.gen_range(1..=3);
+        classes
+            .choose_multiple(rng, count)
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+
+    let mut builder = PasswordRulesBuilder::new();
+    let min_length = rng.gen_range(0..=24);
+    if rng.gen_bool(0.7) {
+        builder = builder.min_length(min_length);
+    }
+    if rng.gen_bool(0.5) {
+        builder = builder.max_length(min_length + rng.gen_range(0..=16));
+    }
+    if rng.gen_bool(0.4) {
+        builder = builder.max_consecutive(rng.gen_range(1..=4));
+    }
+    for _ in 0..rng.gen_range(0..=3) {
+        builder = builder.require(random_classes(rng));
+    }
+    if rng.gen_bool(0.8) {
+        builder = builder.allow(random_classes(rng));
+    }
+    builder.build()
+}
+
+#[test]
+fn passwords_for_random_rules_pass_the_self_check() {
+    let options = GenerateOptions {
+        self_check: true,
+        ..GenerateOptions::default()
+    };
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut generated = 0;
+
+    for _ in 0..1000 {
+        let rules = random_rules(&mut rng);
+        for _ in 0..10 {
+            match rules.generate_with(&options, &mut rng) {
+                Ok(_) => generated += 1,
+                Err(e @ GenerateError::InvalidPassword { .. }) => {
+                    panic!("{} (for {})", e, rules.to_rules_string())
+                }
+                // The rules may be unsatisfiable
+                Err(_) => {}
+            }
+        }
+    }
+
+    // Most of the rules should be satisfiable
+    assert!(generated > 5000, "only generated {} passwords", generated);
+}
+
+/// The shortest and longest of many passwords generated for `rules` with `options`
+fn generated_length_range(rules: &str, options: &GenerateOptions) -> (usize, usize) {
+    let rules = parse_password_rules(rules, true).unwrap();
+    let mut rng = StdRng::seed_from_u64(0);
+    let lengths: Vec<usize> = (0..1000)
+        .map(|_| {
+            let password = rules.generate_with(options, &mut rng).unwrap();
+            password.chars().count()
+        })
+        .collect();
+    (
+        *lengths.iter().min().unwrap(),
+        *lengths.iter().max().unwrap(),
+    )
+}
+
+#[test]
+fn lengths_span_the_length_bounds() {
+    let options = GenerateOptions::default();
+
+    assert_eq!(
+        generated_length_range("minlength: 8; maxlength: 12;", &options),
+        (8, 12)
+    );
+    assert_eq!(
+        generated_length_range("minlength: 10; maxlength: 10; allowed: digit;", &options),
+        (10, 10)
+    );
+    assert_eq!(
+        generated_length_range("maxlength: 4; required: upper; required: digit;", &options),
+        (2, 4)
+    );
+    assert_eq!(
+        generated_length_range("minlength: 24; allowed: unicode;", &options),
+        (24, 24)
+    );
+}
+
+#[test]
+fn default_max_length_bounds_unbounded_rules() {
+    assert_eq!(
+        generated_length_range("minlength: 12;", &GenerateOptions::default()),
+        (12, 20)
+    );
+
+    let options = GenerateOptions {
+        default_max_length: 64,
+        ..GenerateOptions::default()
+    };
+    assert_eq!(generated_length_range("minlength: 32;", &options), (32, 64));
+    assert_eq!(
+        generated_length_range("required: lower;", &options),
+        (1, 64)
+    );
+}
+
+#[test]
+fn min_length_over_max_length_is_an_error() {
+    let rules = parse_password_rules("minlength: 12; maxlength: 8;", true).unwrap();
+
+    assert_eq!(
+        rules.generate(&mut StdRng::seed_from_u64(0)),
+        Err(GenerateError::MinLengthExceedsMaxLength {
+            min_length: 12,
+            max_length: 8
+        })
+    );
+}
+
+/// Whether `password` contains a character from one of the classes in every required group
+fn covers_required(rules: &PasswordRules, password: &str) -> bool {
+    rules.required.iter().all(|classes| {
+        password
+            .chars()
+            .any(|c| classes.iter().any(|class| class.contains(c)))
+    })
+}
+
+#[test]
+fn every_required_group_is_covered() {
+    for rules in [
+        "required: upper; required: lower; required: digit; required: [-];",
+        "maxlength: 4; required: upper; required: lower; required: digit; required: [-];",
+        "minlength: 3; maxlength: 3; required: [a]; required: [b]; required: [c]; allowed: digit;",
+        "maxlength: 2; max-consecutive: 1; required: [x]; required: [x, y];",
+        "minlength: 30; required: [!]; allowed: lower;",
+    ] {
+        let parsed = parse_password_rules(rules, true).unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..1000 {
+            let password = parsed.generate(&mut rng).unwrap();
+            assert!(
+                covers_required(&parsed, &password),
+                "{:?} doesn't cover every required group of {:?}",
+                password,
+                rules
+            );
+        }
+    }
+}
+
+#[test]
+fn required_groups_that_exactly_fill_max_length_are_each_used_once() {
+    let rules = parse_password_rules(
+        "maxlength: 3; required: [a]; required: [b]; required: [c];",
+        true,
+    )
+    .unwrap();
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for _ in 0..100 {
+        let mut chars: Vec<char> = rules.generate(&mut rng).unwrap().chars().collect();
+        chars.sort_unstable();
+        assert_eq!(chars, vec!['a', 'b', 'c']);
+    }
+}
+
+#[test]
+fn more_required_groups_than_max_length_is_an_error() {
+    let rules = parse_password_rules(
+        "maxlength: 2; required: upper; required: lower; required: digit;",
+        true,
+    )
+    .unwrap();
+
+    assert_eq!(
+        rules.generate(&mut StdRng::seed_from_u64(0)),
+        Err(GenerateError::TooManyRequired {
+            required: 3,
+            max_length: 2
+        })
+    );
+}
+
+#[test]
+fn required_groups_without_characters_are_an_error() {
+    let rules = parse_password_rules("required: unicode; required: digit;", true).unwrap();
+    let options = GenerateOptions {
+        unicode_pool: Vec::new(),
+        ..GenerateOptions::default()
+    };
+
+    assert_eq!(
+        rules.generate_with(&options, &mut StdRng::seed_from_u64(0)),
+        Err(GenerateError::EmptyRequiredGroup {
+            classes: vec![CharacterClass::Unicode]
+        })
+    );
+}
+
+#[test]
+fn huge_lengths_are_capped() {
+    let strongest = GenerateOptions {
+        mode: GenerateMode::Strongest,
+        ..GenerateOptions::default()
+    };
+    let rules = parse_password_rules(
+        "maxlength: 4000000000; max-consecutive: 4000000000; allowed: unicode;",
+        true,
+    )
+    .unwrap();
+    let mut rng = StdRng::seed_from_u64(0);
+
+    let password = rules.generate_with(&strongest, &mut rng).unwrap();
+    assert_eq!(password.chars().count(), MAX_GENERATED_LENGTH as usize);
+    // Neither limit can be broken by a password of a sensible length, and `unicode` allows
+    // everything else
+    assert_eq!(rules.generate_failing(&mut rng), None);
+
+    let rules = parse_password_rules("minlength: 4000000000;", true).unwrap();
+    assert_eq!(
+        rules.generate(&mut rng),
+        Err(GenerateError::MinLengthTooLong {
+            min_length: 4_000_000_000
+        })
+    );
+    let (password, _) = rules.generate_failing(&mut rng).unwrap();
+    assert!(password.chars().count() <= MAX_GENERATED_LENGTH as usize);
+}
+
+#[test]
+fn strongest_and_minimal_modes_pick_the_extreme_lengths() {
+    let mode = |mode| GenerateOptions {
+        mode,
+        ..GenerateOptions::default()
+    };
+    let strongest = mode(GenerateMode::Strongest);
+    let minimal = mode(GenerateMode::Minimal);
+
+    let bounded = "minlength: 8; maxlength: 12; required: upper; required: digit;";
+    assert_eq!(generated_length_range(bounded, &strongest), (12, 12));
+    assert_eq!(generated_length_range(bounded, &minimal), (8, 8));
+
+    // Without a maximum, the strongest passwords are as long as the default maximum, and without
+    // a minimum, the minimal passwords only have room for the required groups
+    let unbounded = "required: upper; required: digit; allowed: lower;";
+    assert_eq!(generated_length_range(unbounded, &strongest), (20, 20));
+    assert_eq!(generated_length_range(unbounded, &minimal), (2, 2));
+}
+
+#[test]
+fn strongest_passwords_use_the_whole_alphabet() {
+    let rules =
+        parse_password_rules("maxlength: 64; required: digit; allowed: [abc];", true).unwrap();
+    let options = GenerateOptions {
+        mode: GenerateMode::Strongest,
+        ..GenerateOptions::default()
+    };
+    let mut rng = StdRng::seed_from_u64(0);
+
+    let mut seen: Vec<char> = (0..100)
+        .flat_map(|_| {
+            rules
+                .generate_with(&options, &mut rng)
+                .unwrap()
+                .chars()
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    seen.sort_unstable();
+    seen.dedup();
+    assert_eq!(seen, "0123456789abc".chars().collect::<Vec<_>>());
+}
+
+#[test]
+fn modes_parse_from_their_names() {
+    for mode in [
+        GenerateMode::Random,
+        GenerateMode::Strongest,
+        GenerateMode::Minimal,
+    ] {
+        assert_eq!(mode.to_string().parse::<GenerateMode>(), Ok(mode));
+    }
+    assert!("longest".parse::<GenerateMode>().is_err());
+}
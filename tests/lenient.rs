@@ -0,0 +1,100 @@
+use password_rules_checker::syntax::{blank_empty_clauses, literal_trailing_hyphens};
+use password_rules_checker::{parse_password_rules_lenient, parse_password_rules_recover};
+use password_rules_parser::{parse_password_rules, CharacterClass};
+
+#[test]
+fn stray_semicolons_are_tolerated() {
+    let expected = parse_password_rules("minlength: 8", false).unwrap();
+
+    for rules in [
+        "minlength: 8;",
+        "minlength: 8;;",
+        ";minlength: 8",
+        " ; minlength: 8; ;",
+    ]
+    .iter()
+    {
+        assert_eq!(
+            parse_password_rules_lenient(rules, false).unwrap(),
+            expected,
+            "{:?} didn't parse leniently",
+            rules
+        );
+    }
+}
+
+#[test]
+fn blanking_keeps_offsets() {
+    assert_eq!(blank_empty_clauses(";minlength: 8;;"), " minlength: 8; ");
+    // Semicolons inside of custom classes don't end clauses
+    assert_eq!(blank_empty_clauses("allowed: [;]; ;"), "allowed: [;];  ");
+    assert_eq!(blank_empty_clauses("allowed: [;]];;"), "allowed: [;]]; ");
+}
+
+#[test]
+fn errors_point_into_the_original_rules() {
+    let e = parse_password_rules_lenient(";;minlength: x", false).unwrap_err();
+
+    assert_eq!(
+        e.expectations[0].index,
+        parse_password_rules("  minlength: x", false)
+            .unwrap_err()
+            .expectations[0]
+            .index
+    );
+}
+
+#[test]
+fn hyphens_at_class_boundaries_are_literal() {
+    let expected = vec![CharacterClass::Custom(vec!['a', '-'])];
+
+    for rules in ["allowed: [-a];", "allowed: [a-];", "allowed: [-a-];"] {
+        assert_eq!(
+            parse_password_rules_lenient(rules, false).unwrap().allowed,
+            expected,
+            "{:?} didn't parse leniently",
+            rules
+        );
+    }
+}
+
+#[test]
+fn hyphens_inside_classes_are_still_rejected() {
+    assert!(parse_password_rules_lenient("allowed: [a-c];", false).is_err());
+
+    // The trailing hyphen is rewritten in place, so the error still points at the bad hyphen
+    let e = parse_password_rules_lenient("allowed: [a-c-];", false).unwrap_err();
+    assert_eq!(e.expectations[0].index, 11);
+}
+
+#[test]
+fn trailing_hyphens_keep_offsets() {
+    assert_eq!(
+        literal_trailing_hyphens("allowed: [ab-];"),
+        "allowed: [-ab];"
+    );
+    assert_eq!(
+        literal_trailing_hyphens("allowed: [-é-];"),
+        "allowed: [-é-];"
+    );
+    assert_eq!(literal_trailing_hyphens("allowed: [-];"), "allowed: [-];");
+    assert_eq!(
+        literal_trailing_hyphens("allowed: [a-]];"),
+        "allowed: [a-]];"
+    );
+}
+
+#[test]
+fn recovery_only_reports_clauses_the_lenient_parse_rejects() {
+    let (rules, errors) =
+        parse_password_rules_recover(";allowed: [abc-];; minlength: x; maxlength: 8", false);
+
+    assert_eq!(errors.len(), 1);
+    // The index is relative to the original rules, leading semicolon and all
+    assert_eq!(errors[0].expectations[0].index, 30);
+    assert_eq!(
+        rules.allowed,
+        vec![CharacterClass::Custom(vec!['a', 'b', 'c', '-'])]
+    );
+    assert_eq!(rules.max_length, Some(8));
+}
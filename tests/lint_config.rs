@@ -0,0 +1,41 @@
+use password_rules_checker::lint::LintConfig;
+use password_rules_checker::{Lint, Severity};
+use password_rules_parser::parse_password_rules;
+
+fn findings(config: &str, rules: &str) -> Vec<(&'static str, Severity)> {
+    let config: LintConfig = toml::from_str(config).unwrap();
+    let mut findings = parse_password_rules(rules, false).unwrap().findings();
+    config.apply(&mut findings);
+    findings.into_iter().map(|f| (f.lint, f.severity)).collect()
+}
+
+#[test]
+fn the_default_config_changes_nothing() {
+    let rules = "minlength: 8; required: upper; allowed: upper, lower;";
+    let unconfigured = parse_password_rules(rules, false).unwrap().findings();
+    let mut configured = unconfigured.clone();
+    LintConfig::default().apply(&mut configured);
+
+    assert_eq!(configured, unconfigured);
+}
+
+#[test]
+fn lints_can_be_allowed_and_have_their_severity_changed() {
+    let config = r#"
+        [lints]
+        missing-maxlength = "allow"
+        missing-max-consecutive = "error"
+    "#;
+
+    assert_eq!(
+        findings(config, "minlength: 8; allowed: lower;"),
+        vec![("missing-max-consecutive", Severity::Error)]
+    );
+}
+
+#[test]
+fn misspelled_lints_are_reported() {
+    let config: LintConfig = toml::from_str("[lints]\nmissing-max-length = \"allow\"").unwrap();
+
+    assert_eq!(config.unknown_lints(), vec!["missing-max-length"]);
+}
@@ -0,0 +1,80 @@
+use password_rules_checker::Merge;
+use password_rules_parser::{parse_password_rules, CharacterClass, PasswordRules};
+
+/// Parse `rules` without supplying the default, so that empty `allowed` properties stay empty
+fn parse(rules: &str) -> PasswordRules {
+    parse_password_rules(rules, false).unwrap()
+}
+
+#[test]
+fn disjoint_allowed_classes_cant_be_merged() {
+    assert_eq!(
+        parse("allowed: lower;").merge(&parse("allowed: digit;")),
+        None
+    );
+}
+
+#[test]
+fn required_classes_are_permitted() {
+    // `required: digit;` only permits digits, so merging it doesn't let in anything else
+    assert_eq!(
+        parse("required: digit;").merge(&parse("allowed: lower;")),
+        None
+    );
+
+    let merged = parse("required: digit;")
+        .merge(&parse("allowed: lower, digit;"))
+        .unwrap();
+    assert_eq!(merged.allowed, vec![CharacterClass::Digit]);
+    assert_eq!(merged.required, vec![vec![CharacterClass::Digit]]);
+}
+
+#[test]
+fn empty_rules_permit_ascii_printable() {
+    let merged = parse("minlength: 8;")
+        .merge(&parse("allowed: lower;"))
+        .unwrap();
+    assert_eq!(merged.allowed, vec![CharacterClass::Lower]);
+
+    let merged = parse("minlength: 8;")
+        .merge(&parse("maxlength: 16;"))
+        .unwrap();
+    assert_eq!(merged.allowed, vec![CharacterClass::AsciiPrintable]);
+}
+
+#[test]
+fn unicode_permits_whatever_the_other_rules_do() {
+    let merged = parse("allowed: unicode;")
+        .merge(&parse("allowed: upper, [_];"))
+        .unwrap();
+    assert_eq!(
+        merged.allowed,
+        vec![CharacterClass::Upper, CharacterClass::Custom(vec!['_'])]
+    );
+
+    let merged = parse("allowed: unicode;")
+        .merge(&parse("allowed: unicode;"))
+        .unwrap();
+    assert_eq!(merged.allowed, vec![CharacterClass::Unicode]);
+}
+
+#[test]
+fn equivalent_required_groups_are_kept_once() {
+    // The parser would put these groups in canonical order, so build them by hand
+    let rules = PasswordRules {
+        required: vec![
+            vec![CharacterClass::Upper, CharacterClass::Digit],
+            vec![CharacterClass::Custom(vec!['a', 'b'])],
+        ],
+        ..PasswordRules::default()
+    };
+    let other = PasswordRules {
+        required: vec![
+            vec![CharacterClass::Digit, CharacterClass::Upper],
+            vec![CharacterClass::Custom(vec!['b', 'a'])],
+        ],
+        ..PasswordRules::default()
+    };
+
+    assert_eq!(rules.merge(&other).unwrap().required, rules.required);
+}
@@ -0,0 +1,41 @@
+#![cfg(feature = "nfc")]
+
+use password_rules_checker::{CharacterClassExt, PasswordRulesExt, Validate};
+use password_rules_parser::{CharacterClass, PasswordRules};
+use unicode_normalization::UnicodeNormalization;
+
+#[test]
+fn built_in_classes_are_unchanged() {
+    for class in [
+        CharacterClass::Upper,
+        CharacterClass::AsciiPrintable,
+        CharacterClass::Unicode,
+    ] {
+        assert_eq!(class.to_nfc(), class);
+    }
+}
+
+#[test]
+fn composed_and_decomposed_classes_are_equal() {
+    let composed = CharacterClass::Custom("ñé".chars().collect());
+    let decomposed = CharacterClass::Custom("n\u{303}e\u{301}".chars().collect());
+
+    assert_eq!(composed.to_nfc(), decomposed.to_nfc());
+    assert_eq!(composed.to_nfc(), CharacterClass::Custom(vec!['é', 'ñ']));
+}
+
+#[test]
+fn normalized_rules_accept_normalized_passwords() {
+    let rules = PasswordRules {
+        allowed: vec![
+            CharacterClass::Lower,
+            CharacterClass::Custom("e\u{301}".chars().collect()),
+        ],
+        required: vec![vec![CharacterClass::Custom("e\u{301}".chars().collect())]],
+        ..PasswordRules::default()
+    };
+    let password: String = "caf\u{e9}".nfc().collect();
+
+    assert!(!rules.validate(&password));
+    assert!(rules.to_nfc().validate(&password));
+}
@@ -0,0 +1,72 @@
+use password_rules_checker::onepassword::{OnePasswordSet, ToOnePassword};
+use password_rules_parser::parse_password_rules;
+
+#[test]
+fn ascii_printable_is_every_set() {
+    let recipe = parse_password_rules("minlength: 12;", true)
+        .unwrap()
+        .to_onepassword();
+
+    assert_eq!(
+        recipe.allowed,
+        vec![
+            OnePasswordSet::Uppercase,
+            OnePasswordSet::Lowercase,
+            OnePasswordSet::Digits,
+            OnePasswordSet::Symbols,
+        ]
+    );
+    assert!(recipe.required.is_empty());
+    assert!(recipe.notes.is_empty());
+}
+
+#[test]
+fn required_sets_are_allowed() {
+    let recipe = parse_password_rules(
+        "maxlength: 16; max-consecutive: 2; required: digit; required: digit; allowed: [abc];",
+        false,
+    )
+    .unwrap()
+    .to_onepassword();
+
+    assert_eq!(
+        recipe.allowed,
+        vec![
+            OnePasswordSet::Digits,
+            OnePasswordSet::Custom("abc".to_string())
+        ]
+    );
+    // Duplicate groups are dropped
+    assert_eq!(recipe.required, vec![vec![OnePasswordSet::Digits]]);
+    assert_eq!((recipe.length.min, recipe.length.max), (None, Some(16)));
+    assert_eq!(recipe.max_consecutive, Some(2));
+}
+
+#[test]
+fn unicode_is_approximated_with_a_note() {
+    let recipe = parse_password_rules("required: unicode; allowed: unicode;", false)
+        .unwrap()
+        .to_onepassword();
+
+    assert_eq!(recipe.allowed.len(), 4);
+    assert_eq!(recipe.notes.len(), 1);
+    assert!(recipe.notes[0].contains("unicode"), "{:?}", recipe.notes);
+}
+
+#[test]
+fn recipes_serialize_with_camel_case_fields() {
+    let recipe = parse_password_rules("max-consecutive: 3; allowed: special, [x];", false)
+        .unwrap()
+        .to_onepassword();
+
+    assert_eq!(
+        serde_json::to_value(&recipe).unwrap(),
+        serde_json::json!({
+            "length": { "min": null, "max": null },
+            "allowed": ["symbols", { "custom": "x" }],
+            "required": [],
+            "maxConsecutive": 3,
+            "notes": [],
+        })
+    );
+}
@@ -0,0 +1,18 @@
+use password_rules_checker::ErrorSpan;
+use password_rules_parser::parse_password_rules;
+
+#[test]
+fn plain_errors_have_no_color_even_when_coloring_is_forced() {
+    let rules = "minlength: 8; allowed: upper, foo;";
+    let e = parse_password_rules(rules, false).unwrap_err();
+
+    colored::control::set_override(true);
+    let plain = e.to_string_plain(rules).unwrap();
+    let colored = e.to_string_pretty(rules).unwrap();
+    colored::control::set_override(false);
+    let uncolored = e.to_string_pretty(rules).unwrap();
+
+    assert!(colored.contains('\x1b'));
+    assert!(!plain.contains('\x1b'));
+    assert_eq!(plain, uncolored);
+}
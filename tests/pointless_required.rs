@@ -0,0 +1,26 @@
+use password_rules_checker::Lint;
+use password_rules_parser::{parse_password_rules, CharacterClass};
+
+fn pointless_required(rules: &str) -> Vec<Vec<CharacterClass>> {
+    parse_password_rules(rules, false)
+        .unwrap()
+        .pointless_required()
+}
+
+#[test]
+fn required_classes_covering_every_permitted_character_are_pointless() {
+    assert_eq!(
+        pointless_required("allowed: lower; required: lower, digit; required: digit;"),
+        vec![vec![CharacterClass::Lower, CharacterClass::Digit]]
+    );
+    assert_eq!(
+        pointless_required("allowed: [abc]; required: lower;"),
+        vec![vec![CharacterClass::Lower]]
+    );
+}
+
+#[test]
+fn required_classes_are_useful_if_other_characters_are_permitted() {
+    assert!(pointless_required("allowed: upper; required: digit;").is_empty());
+    assert!(pointless_required("allowed: unicode; required: digit;").is_empty());
+}
@@ -0,0 +1,31 @@
+use password_rules_checker::quirks::{load_quirk_entries, read_quirk_entries, LoadError};
+use std::io::BufReader;
+
+const QUIRKS: &str = r#"{
+    "b.com": {"password-rules": "minlength: 8;"},
+    "a.com": {"password-rules": "required: upper;"},
+    "B.com": {"password-rules": "minlength: 10;"}
+}"#;
+
+#[test]
+fn entries_are_read_from_a_reader_in_order() {
+    let entries = read_quirk_entries(BufReader::new(QUIRKS.as_bytes())).unwrap();
+
+    let sites: Vec<&str> = entries.0.iter().map(|(site, _)| site.as_str()).collect();
+    assert_eq!(sites, vec!["b.com", "a.com", "B.com"]);
+    assert_eq!(entries.colliding_keys(), vec![("b.com", "B.com")]);
+}
+
+#[test]
+fn load_errors_name_the_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("password-rules-checker-invalid.json");
+    std::fs::write(&path, r#"{"a.com": {"password-rules": 8}}"#).unwrap();
+
+    let e = load_quirk_entries(&path).unwrap_err();
+
+    assert!(matches!(e, LoadError::Parse { .. }));
+    assert!(e
+        .to_string()
+        .contains("password-rules-checker-invalid.json"));
+}
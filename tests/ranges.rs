@@ -0,0 +1,110 @@
+use password_rules_checker::syntax::{expand_classes, expand_ranges, locate_expanded_error};
+use password_rules_checker::{ErrorKind, ErrorSpan, PasswordRulesErrorExt};
+use password_rules_parser::{parse_password_rules, CharacterClass};
+use std::time::{Duration, Instant};
+
+fn allowed_chars(rules: &str) -> Vec<char> {
+    let rules = parse_password_rules(&expand_ranges(rules).unwrap(), false).unwrap();
+    let mut chars: Vec<char> = rules.allowed.iter().flat_map(|c| c.chars()).collect();
+    chars.sort_unstable();
+    chars
+}
+
+#[test]
+fn ranges_expand() {
+    assert_eq!(
+        expand_ranges("allowed: [a-d];").unwrap(),
+        "allowed: [abcd];"
+    );
+    assert_eq!(
+        allowed_chars("allowed: [a-f0-9];"),
+        "0123456789abcdef".chars().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn full_ranges_become_builtin_classes() {
+    let rules = parse_password_rules(&expand_ranges("allowed: [a-z];").unwrap(), false).unwrap();
+    assert_eq!(rules.allowed, vec![CharacterClass::Lower]);
+}
+
+#[test]
+fn leading_and_trailing_hyphens_are_literal() {
+    assert_eq!(expand_ranges("allowed: [-a];").unwrap(), "allowed: [-a];");
+    assert_eq!(expand_ranges("allowed: [a-];").unwrap(), "allowed: [-a];");
+    assert_eq!(allowed_chars("allowed: [-a-c];"), vec!['-', 'a', 'b', 'c']);
+}
+
+#[test]
+fn mixed_ranges_and_literals() {
+    assert_eq!(
+        allowed_chars("allowed: [x0-2_];"),
+        vec!['0', '1', '2', '_', 'x']
+    );
+    assert_eq!(
+        expand_ranges("required: [a-c]]; allowed: lower, [!-#];").unwrap(),
+        "required: [abc]]; allowed: lower, [!\"#];"
+    );
+}
+
+#[test]
+fn rules_without_ranges_are_unchanged() {
+    let rules = "minlength: 8; required: upper; allowed: [_!]];";
+    assert_eq!(expand_ranges(rules).unwrap(), rules);
+}
+
+#[test]
+fn descending_ranges_are_errors() {
+    let rules = " allowed: lower, [xz-a];";
+    let e = expand_ranges(rules).unwrap_err();
+
+    // The error points at the start of the range, relative to the trimmed input like the
+    // parser's errors
+    assert_eq!(e.expectations.len(), 1);
+    assert_eq!(e.expectations[0].index, 18);
+    assert_eq!((e.expectations[0].line, e.expectations[0].column), (1, 19));
+    assert_eq!(e.span(rules).unwrap().start, 19);
+}
+
+#[test]
+fn expanded_errors_point_into_the_original_rules() {
+    // The escape shrinks the first class, so the range error is found earlier in the string the
+    // ranges are expanded in
+    let rules = r"allowed: [ab\-], [c-a];";
+    let e = expand_classes(rules).unwrap_err();
+    assert_eq!(e.expectations[0].index, 18);
+
+    // Parse errors after a rewritten class move by however much it changed length
+    let rules = "allowed: [a-z0-9]; minlength: x;";
+    let expanded = expand_classes(rules).unwrap();
+    let mut e = parse_password_rules(&expanded, false).unwrap_err();
+    locate_expanded_error(&mut e, rules, &expanded);
+    assert_eq!(e.expectations[0].index, rules.find('x').unwrap());
+    assert_eq!(
+        e.expectations[0].column as usize,
+        rules.find('x').unwrap() + 1
+    );
+}
+
+#[test]
+fn full_unicode_ranges_expand_quickly() {
+    let started = Instant::now();
+    let rules = "allowed: [!-\u{10FFFF}]; minlength: 8;";
+    let expanded = expand_classes(rules).unwrap();
+    let parsed = parse_password_rules(&expanded, false).unwrap();
+
+    // The parser drops the non-ASCII characters, leaving everything from `!` on
+    assert_eq!(parsed.min_length, Some(8));
+    assert!(parsed.allowed.contains(&CharacterClass::Upper));
+    assert!(
+        started.elapsed() < Duration::from_secs(10),
+        "expanding took {:?}",
+        started.elapsed()
+    );
+}
+
+#[test]
+fn descending_ranges_have_their_own_kind() {
+    let e = expand_ranges("allowed: [z-a];").unwrap_err();
+    assert_eq!(e.kind(), ErrorKind::InvalidRange);
+}
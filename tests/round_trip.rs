@@ -0,0 +1,127 @@
+use password_rules_checker::{Defaults, PasswordRulesBuilder, SemanticEq, ToRulesString};
+use password_rules_parser::{parse_password_rules, CharacterClass, PasswordRules};
+
+/// Valid rules strings covering every property, every built-in class, and custom classes
+const CORPUS: &[&str] = &[
+    "minlength: 8;",
+    "maxlength: 64",
+    "max-consecutive: 2;",
+    "required: upper;",
+    "allowed: lower;",
+    "required: digit; allowed: special;",
+    "allowed: ascii-printable;",
+    "allowed: unicode;",
+    "required: upper, lower; required: digit;",
+    "allowed: upper, lower, digit, special;",
+    "allowed: [abc];",
+    "allowed: [-];",
+    "allowed: []];",
+    "allowed: [-_!]];",
+    "required: [-.*]], digit; allowed: lower;",
+    "required: [ ];",
+    "allowed: [~!@#$%^&*()];",
+    "minlength: 12; maxlength: 16; max-consecutive: 3; required: lower; required: upper; \
+     required: digit; required: [-!#$]; allowed: [_.];",
+    "MINLENGTH: 6; ALLOWED: UPPER, [xyz];",
+    "minlength: 8; minlength: 10; maxlength: 20; maxlength: 18;",
+    "minlength: 20; maxlength: 4;",
+];
+
+fn assert_round_trips(rules: &str, supply_default: bool) {
+    let parsed = parse_password_rules(rules, supply_default)
+        .unwrap_or_else(|e| panic!("{:?} failed to parse: {}", rules, e));
+    let rendered = parsed.to_rules_string();
+    let reparsed = parse_password_rules(&rendered, supply_default)
+        .unwrap_or_else(|e| panic!("{:?} (from {:?}) failed to parse: {}", rendered, rules, e));
+
+    assert!(
+        parsed.semantically_eq(&reparsed),
+        "{:?} rendered as {:?}, which parses differently:\n{:#?}\n{:#?}",
+        rules,
+        rendered,
+        parsed,
+        reparsed
+    );
+}
+
+#[test]
+fn rules_round_trip() {
+    for rules in CORPUS {
+        assert_round_trips(rules, false);
+    }
+}
+
+#[test]
+fn rules_with_supplied_defaults_round_trip() {
+    for rules in CORPUS {
+        assert_round_trips(rules, true);
+    }
+}
+
+#[test]
+fn rendering_is_stable() {
+    for rules in CORPUS {
+        let rendered = parse_password_rules(rules, false)
+            .unwrap()
+            .to_rules_string();
+        let rerendered = parse_password_rules(&rendered, false)
+            .unwrap()
+            .to_rules_string();
+        assert_eq!(rendered, rerendered);
+    }
+}
+
+#[test]
+fn built_rules_round_trip_exactly() {
+    let builders = vec![
+        PasswordRulesBuilder::new().min_length(8).max_length(64),
+        PasswordRulesBuilder::new()
+            .max_consecutive(3)
+            .require(vec![CharacterClass::Upper])
+            .require(vec![
+                CharacterClass::Digit,
+                CharacterClass::Custom(vec!['-', ']']),
+            ])
+            .allow(vec![CharacterClass::Lower]),
+        // Not canonical as written, so the builder has to merge the classes like the parser does
+        PasswordRulesBuilder::new()
+            .allow(vec![
+                CharacterClass::Custom(vec!['c', 'b', 'a']),
+                CharacterClass::Upper,
+            ])
+            .allow(vec![CharacterClass::Custom(vec!['A', '1'])])
+            .require(vec![CharacterClass::Lower, CharacterClass::Unicode]),
+        PasswordRulesBuilder::new().allow(vec![
+            CharacterClass::Upper,
+            CharacterClass::Lower,
+            CharacterClass::Digit,
+            CharacterClass::Special,
+        ]),
+    ];
+
+    for builder in builders {
+        let built = builder.build();
+        let rendered = built.to_rules_string();
+        let reparsed = parse_password_rules(&rendered, false)
+            .unwrap_or_else(|e| panic!("{:?} failed to parse: {}", rendered, e));
+        assert_eq!(built, reparsed, "{:?} parses differently", rendered);
+        assert_eq!(rendered, reparsed.to_rules_string());
+    }
+}
+
+#[test]
+fn empty_builders_write_an_empty_string() {
+    let builder = PasswordRulesBuilder::new();
+
+    assert_eq!(builder.build(), PasswordRules::default());
+    assert_eq!(builder.to_string(), "");
+    assert!(parse_password_rules(&builder.to_string(), false).is_err());
+
+    // Allowing the default class gives a string that does round trip
+    let builder = builder.allow(vec![CharacterClass::AsciiPrintable]);
+    assert_eq!(builder.build(), PasswordRules::apple_default());
+    assert_eq!(
+        parse_password_rules(&builder.to_string(), false).unwrap(),
+        builder.build()
+    );
+}
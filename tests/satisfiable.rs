@@ -0,0 +1,66 @@
+use password_rules_checker::{Lint, Unsatisfiable};
+use password_rules_parser::parse_password_rules;
+
+/// Parse `rules`, which are expected to be valid syntax
+fn parse(rules: &str) -> password_rules_parser::PasswordRules {
+    parse_password_rules(rules, true).unwrap()
+}
+
+#[test]
+fn ordinary_rules_are_satisfiable() {
+    assert!(
+        parse("minlength: 8; maxlength: 20; required: upper; required: digit;").is_satisfiable()
+    );
+    assert!(parse("allowed: lower;").is_satisfiable());
+}
+
+#[test]
+fn min_length_above_max_length_is_unsatisfiable() {
+    assert_eq!(
+        parse("minlength: 12; maxlength: 8;").unsatisfiable_reasons(),
+        vec![Unsatisfiable::MinLengthExceedsMaxLength {
+            min_length: 12,
+            max_length: 8
+        }]
+    );
+}
+
+#[test]
+fn too_many_disjoint_required_groups_are_unsatisfiable() {
+    assert_eq!(
+        parse("maxlength: 2; required: upper; required: lower; required: digit;")
+            .unsatisfiable_reasons(),
+        vec![Unsatisfiable::TooManyRequired {
+            required: 3,
+            max_length: 2
+        }]
+    );
+
+    // Overlapping groups can share a character
+    assert!(parse("maxlength: 1; required: upper, digit; required: digit;").is_satisfiable());
+}
+
+#[test]
+fn max_consecutive_zero_is_unsatisfiable() {
+    assert_eq!(
+        parse("max-consecutive: 0; required: lower;").unsatisfiable_reasons(),
+        vec![Unsatisfiable::MaxConsecutiveZero]
+    );
+}
+
+#[test]
+fn repeating_the_only_character_is_unsatisfiable() {
+    assert_eq!(
+        parse("minlength: 3; max-consecutive: 2; allowed: [a];").unsatisfiable_reasons(),
+        vec![Unsatisfiable::OnlyCharacterRepeated {
+            character: 'a',
+            min_length: 3,
+            max_consecutive: 2
+        }]
+    );
+}
+
+#[test]
+fn required_classes_are_implicitly_allowed() {
+    assert!(parse("required: digit; allowed: lower;").is_satisfiable());
+}
@@ -0,0 +1,49 @@
+use password_rules_checker::{ClassKey, SemanticEq, SemanticKey};
+use password_rules_parser::{parse_password_rules, CharacterClass};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+fn hash(value: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn semantically_equal_rules_make_equal_keys() {
+    let pairs = [
+        ("minlength: 8;", "minlength: 8; allowed: ascii-printable;"),
+        (
+            "required: upper; required: digit; allowed: upper, lower;",
+            "required: digit; required: upper; allowed: lower;",
+        ),
+        ("required: [abc];", "required: [cba]; required: [abc];"),
+    ];
+
+    for (rules, other_rules) in pairs.iter() {
+        let rules = parse_password_rules(rules, true).unwrap();
+        let other_rules = parse_password_rules(other_rules, true).unwrap();
+        assert!(rules.semantically_eq(&other_rules));
+
+        let (key, other_key) = (SemanticKey::new(&rules), SemanticKey::new(&other_rules));
+        assert_eq!(key, other_key);
+        assert_eq!(hash(&key), hash(&other_key));
+    }
+}
+
+#[test]
+fn different_rules_make_different_keys() {
+    let rules = parse_password_rules("minlength: 8; required: upper;", true).unwrap();
+    let other_rules = parse_password_rules("minlength: 8; required: lower;", true).unwrap();
+
+    assert_ne!(SemanticKey::new(&rules), SemanticKey::new(&other_rules));
+}
+
+#[test]
+fn class_keys_ignore_the_order_of_custom_characters() {
+    let key = ClassKey::new(&CharacterClass::Custom(vec!['b', 'a', 'b']));
+    let other_key = ClassKey::new(&CharacterClass::Custom(vec!['a', 'b']));
+
+    assert_eq!(key, other_key);
+    assert_eq!(hash(&key), hash(&other_key));
+}
@@ -0,0 +1,45 @@
+use password_rules_checker::{Lint, Validate, Violation};
+use password_rules_parser::parse_password_rules;
+
+#[test]
+fn unicode_allows_non_ascii_characters() {
+    let rules = parse_password_rules("minlength: 4; allowed: unicode;", true).unwrap();
+
+    for password in ["pässwörd", "🔑🔑🔑🔑", "contraseña", "пароль", "密码密码"].iter()
+    {
+        assert!(
+            rules.validate(password),
+            "{:?} doesn't satisfy the rules: {}",
+            password,
+            rules.check(password)
+        );
+    }
+}
+
+#[test]
+fn unicode_length_is_counted_in_characters() {
+    let rules = parse_password_rules("maxlength: 3; allowed: unicode;", true).unwrap();
+
+    assert!(rules.validate("ééé"));
+    assert!(!rules.validate("éééé"));
+}
+
+#[test]
+fn ascii_printable_rejects_non_ascii_characters() {
+    let rules = parse_password_rules("allowed: ascii-printable;", true).unwrap();
+
+    assert_eq!(
+        rules.check("pässword").violations,
+        vec![Violation::DisallowedCharacter('ä')]
+    );
+}
+
+#[test]
+fn unicode_overlaps_every_other_class() {
+    // A single character can satisfy both groups, since `unicode` contains every digit
+    let rules =
+        parse_password_rules("maxlength: 1; required: unicode; required: digit;", true).unwrap();
+
+    assert!(rules.is_satisfiable());
+    assert!(rules.validate("7"));
+}
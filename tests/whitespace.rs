@@ -0,0 +1,42 @@
+use password_rules_checker::syntax::canonical_whitespace;
+use password_rules_parser::parse_password_rules;
+
+/// Rules that differ from `minlength: 8; required: upper, lower;` only in whitespace
+const VARIANTS: &[&str] = &[
+    "minlength:8;required:upper,lower;",
+    "minlength : 8 ; required : upper , lower ;",
+    "  minlength: 8;   required: upper,   lower;  ",
+    "minlength:\t8;\nrequired: upper,\nlower;",
+];
+
+#[test]
+fn parser_accepts_any_whitespace_around_separators() {
+    let expected = parse_password_rules("minlength: 8; required: upper, lower;", false).unwrap();
+
+    for rules in VARIANTS.iter() {
+        assert_eq!(
+            parse_password_rules(rules, false).unwrap(),
+            expected,
+            "{:?} parsed differently",
+            rules
+        );
+    }
+}
+
+#[test]
+fn whitespace_is_canonicalized() {
+    for rules in VARIANTS.iter() {
+        assert_eq!(
+            canonical_whitespace(rules),
+            "minlength: 8; required: upper, lower;"
+        );
+    }
+}
+
+#[test]
+fn whitespace_inside_custom_classes_is_kept() {
+    assert_eq!(
+        canonical_whitespace("allowed:[ ,;]; minlength:8"),
+        "allowed: [ ,;]; minlength: 8"
+    );
+}